@@ -0,0 +1,143 @@
+use crate::commands::CommandParser;
+use crate::config::Config;
+use std::collections::BTreeMap;
+
+const MAX_DEPTH: usize = 16;
+
+#[derive(Debug, Default)]
+pub struct Aliases {
+    pub aliases: BTreeMap<String, String>,
+    pub macros: BTreeMap<String, Vec<String>>,
+}
+
+impl Aliases {
+    pub fn from_config(config: &Config) -> (Aliases, Vec<String>) {
+        let mut aliases = Aliases::default();
+        let mut warnings = Vec::new();
+
+        for (name, expansion) in &config.aliases {
+            if let Err(e) = aliases.define_alias(name, expansion) {
+                warnings.push(format!("alias {}: {}", name, e));
+            }
+        }
+        for (name, steps) in &config.macros {
+            if let Err(e) = aliases.define_macro(name, steps) {
+                warnings.push(format!("macro {}: {}", name, e));
+            }
+        }
+        (aliases, warnings)
+    }
+
+    pub fn parse_definition(text: &str) -> Result<(String, String), String> {
+        match text.split_once('=') {
+            Some((name, expansion)) => Ok((
+                name.trim().trim_start_matches(':').to_string(),
+                expansion.trim().to_string(),
+            )),
+            None => Err("expected name = command".to_string()),
+        }
+    }
+
+    fn check_name(&self, name: &str) -> Result<(), String> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(format!("invalid name '{}'", name));
+        }
+        if CommandParser::find(name).is_some() {
+            return Err(format!(":{} is a built-in command", name));
+        }
+        Ok(())
+    }
+
+    fn step(command: &str) -> String {
+        command.trim().trim_start_matches(':').to_string()
+    }
+
+    pub fn define_alias(&mut self, name: &str, expansion: &str) -> Result<(), String> {
+        self.check_name(name)?;
+        self.macros.remove(name);
+        if expansion.trim().is_empty() {
+            self.aliases.remove(name);
+        } else {
+            self.aliases.insert(name.to_string(), Self::step(expansion));
+        }
+        Ok(())
+    }
+
+    pub fn define_macro(&mut self, name: &str, steps: &str) -> Result<(), String> {
+        self.check_name(name)?;
+        self.aliases.remove(name);
+        let steps: Vec<String> = steps
+            .split(';')
+            .map(Self::step)
+            .filter(|step| !step.is_empty())
+            .collect();
+        if steps.is_empty() {
+            self.macros.remove(name);
+        } else {
+            self.macros.insert(name.to_string(), steps);
+        }
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.aliases.keys().chain(self.macros.keys()).cloned().collect()
+    }
+
+    pub fn describe(&self, name: &str) -> Option<String> {
+        self.aliases
+            .get(name)
+            .map(|expansion| format!(":{}", expansion))
+            .or_else(|| {
+                self.macros.get(name).map(|steps| {
+                    steps
+                        .iter()
+                        .map(|step| format!(":{}", step))
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                })
+            })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty() && self.macros.is_empty()
+    }
+
+    pub fn expand(&self, line: &str) -> Result<Vec<String>, String> {
+        self.expand_line(line.trim(), 0)
+    }
+
+    fn expand_line(&self, line: &str, depth: usize) -> Result<Vec<String>, String> {
+        let Some(rest) = line.strip_prefix(':') else {
+            return Ok(vec![line.to_string()]);
+        };
+        let (name, args) = rest
+            .split_once(char::is_whitespace)
+            .map_or((rest, ""), |(name, args)| (name, args.trim()));
+
+        if depth > MAX_DEPTH && (self.aliases.contains_key(name) || self.macros.contains_key(name)) {
+            return Err(format!("alias loop while expanding :{}", name));
+        }
+
+        if let Some(expansion) = self.aliases.get(name) {
+            let line = if args.is_empty() {
+                format!(":{}", expansion)
+            } else {
+                format!(":{} {}", expansion, args)
+            };
+            return self.expand_line(&line, depth + 1);
+        }
+
+        if let Some(steps) = self.macros.get(name) {
+            if !args.is_empty() {
+                return Err(format!("macro :{} takes no arguments", name));
+            }
+            let mut lines = Vec::new();
+            for step in steps {
+                lines.extend(self.expand_line(&format!(":{}", step), depth + 1)?);
+            }
+            return Ok(lines);
+        }
+
+        Ok(vec![line.to_string()])
+    }
+}
@@ -0,0 +1,195 @@
+use crate::commands::CommandParser;
+use crate::editor::Editor;
+use crate::file_ops::FileOperations;
+use crate::quick::QuickOptions;
+use crate::search::SearchQuery;
+use std::io::{self, Write};
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_NOT_FOUND: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+pub struct GlobalArgs {
+    pub dir: Option<String>,
+    pub vault: Option<String>,
+    pub rest: Vec<String>,
+}
+
+pub struct Cli;
+
+impl Cli {
+    pub fn run(editor: &mut Editor, args: &[String]) -> io::Result<i32> {
+        match Self::dispatch(editor, args) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(EXIT_OK),
+            result => result,
+        }
+    }
+
+    fn dispatch(editor: &mut Editor, args: &[String]) -> io::Result<i32> {
+        let rest = &args[1..];
+        match args[0].as_str() {
+            "add" => Self::add(editor, rest),
+            "cat" => Self::cat(editor, rest),
+            "ls" => Self::list(editor),
+            "tags" => Self::tags(editor),
+            "search" => Self::search(editor, rest),
+            "new" => Self::new_note(editor, rest),
+            "help" | "-h" | "--help" => {
+                Self::usage();
+                Ok(EXIT_OK)
+            }
+            other => {
+                eprintln!("rustynotes: unknown command '{}'", other);
+                Self::usage();
+                Ok(EXIT_USAGE)
+            }
+        }
+    }
+
+    pub fn parse_global(args: Vec<String>) -> Result<GlobalArgs, String> {
+        let mut global = GlobalArgs {
+            dir: None,
+            vault: None,
+            rest: Vec::new(),
+        };
+        let mut iter = args.into_iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--dir" => global.dir = Some(iter.next().ok_or("--dir requires a path")?),
+                "--vault" => global.vault = Some(iter.next().ok_or("--vault requires a name")?),
+                _ => {
+                    global.rest.push(arg);
+                    global.rest.extend(iter);
+                    break;
+                }
+            }
+        }
+        Ok(global)
+    }
+
+    pub fn usage() {
+        println!("usage: rustynotes [--dir path | --vault name] [command]");
+        println!();
+        println!("  (no command)                    start the interactive editor");
+        println!("  add <note> \"text\" [--tag a,b]   append text to a note, creating it if needed");
+        println!("  add <note> - [--fence lang]     append stdin under a timestamp heading");
+        println!("  cat <note>                      print a note");
+        println!("  ls                              list saved notes");
+        println!("  tags                            list tags with note counts");
+        println!("  search [flags] <term>           search all saved notes (same flags as :search)");
+        println!("  new --template <name> [--name note] [--key value]");
+        println!("                                  create and save a note from a template");
+        println!();
+        println!("notes live in ~/.notes unless --dir, RUSTYNOTES_DIR or the config file say otherwise");
+        println!("with no command and stdin not a terminal, commands are read from stdin");
+        println!("exit codes: 0 ok, 1 nothing found, 2 usage error");
+    }
+
+    fn usage_error(message: &str) -> io::Result<i32> {
+        eprintln!("rustynotes: {}", message);
+        Ok(EXIT_USAGE)
+    }
+
+    fn add(editor: &mut Editor, args: &[String]) -> io::Result<i32> {
+        let Some(note) = args.first() else {
+            return Self::usage_error("usage: rustynotes add <note> \"text\"");
+        };
+        let mut options = match QuickOptions::parse(&args[1..]) {
+            Ok(options) => options,
+            Err(e) => return Self::usage_error(&e),
+        };
+        if options.save.is_some() || options.add.is_some() {
+            return Self::usage_error("add takes the note name as its first argument");
+        }
+
+        let name = note.trim_end_matches(".md");
+        if editor.notes_dir.join(format!("{}.md", name)).exists() {
+            options.add = Some(name.to_string());
+        } else {
+            options.save = Some(name.to_string());
+        }
+
+        Ok(if editor.quick_capture(&options)? {
+            EXIT_OK
+        } else {
+            EXIT_NOT_FOUND
+        })
+    }
+
+    fn cat(editor: &Editor, args: &[String]) -> io::Result<i32> {
+        let Some(note) = args.first() else {
+            return Self::usage_error("usage: rustynotes cat <note>");
+        };
+        let name = editor.resolve_note(note)?;
+        match FileOperations::load_file(&editor.notes_dir, &name) {
+            Ok((content, _, _)) => {
+                io::stdout().lock().write_all(content.as_bytes())?;
+                Ok(EXIT_OK)
+            }
+            Err(e) => {
+                eprintln!("rustynotes: {}", e);
+                Ok(EXIT_NOT_FOUND)
+            }
+        }
+    }
+
+    fn list(editor: &Editor) -> io::Result<i32> {
+        let notes = FileOperations::list_saved_notes(&editor.notes_dir)?;
+        let mut out = io::stdout().lock();
+        for (filename, modified, tags) in &notes {
+            writeln!(out, "{}\t{}\t{}", filename, modified.format("%Y-%m-%d %H:%M"), tags.join(","))?;
+        }
+        Ok(if notes.is_empty() { EXIT_NOT_FOUND } else { EXIT_OK })
+    }
+
+    fn tags(editor: &Editor) -> io::Result<i32> {
+        let (all_tags, tag_counts) = FileOperations::get_all_tags(&editor.notes_dir, None, &[])?;
+        let mut tags: Vec<_> = all_tags.iter().collect();
+        tags.sort_by(|a, b| a.split('/').cmp(b.split('/')));
+        let mut out = io::stdout().lock();
+        for tag in &tags {
+            writeln!(out, "{}\t{}", tag, tag_counts.get(*tag).unwrap_or(&0))?;
+        }
+        Ok(if tags.is_empty() { EXIT_NOT_FOUND } else { EXIT_OK })
+    }
+
+    fn search(editor: &mut Editor, args: &[String]) -> io::Result<i32> {
+        let mut query = match SearchQuery::parse(args) {
+            Ok(query) => query,
+            Err(e) => return Self::usage_error(&e),
+        };
+        query.all_notes = true;
+
+        Ok(if editor.run_search(query)? {
+            EXIT_OK
+        } else {
+            EXIT_NOT_FOUND
+        })
+    }
+
+    fn new_note(editor: &mut Editor, args: &[String]) -> io::Result<i32> {
+        let mut values = match CommandParser::parse_key_values(args) {
+            Ok(values) => values,
+            Err(e) => return Self::usage_error(&e),
+        };
+        let Some(idx) = values.iter().position(|(key, _)| key == "template") else {
+            return Self::usage_error("usage: rustynotes new --template <name> [--name note]");
+        };
+        let template = values.remove(idx).1;
+
+        if let Some((_, name)) = values.iter().find(|(key, _)| key == "name") {
+            let path = editor.notes_dir.join(format!("{}.md", name.trim_end_matches(".md")));
+            if path.exists() {
+                eprintln!("rustynotes: {} already exists", path.display());
+                return Ok(EXIT_USAGE);
+            }
+        }
+
+        if !editor.new_from_template(&template, values)? {
+            return Ok(EXIT_NOT_FOUND);
+        }
+        editor.save_current()?;
+        Ok(EXIT_OK)
+    }
+}
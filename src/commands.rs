@@ -1,7 +1,25 @@
+use crate::aliases::Aliases;
+use crate::export::ExportOptions;
+use crate::frontmatter::MetaFilter;
+use crate::quick::QuickOptions;
+use crate::search::SearchQuery;
+
+#[derive(Debug)]
+pub enum MetaAction {
+    Show,
+    Get(String),
+    Set(String, String),
+    Unset(String),
+}
+
 #[derive(Debug)]
 pub enum Command {
     Write(String),
-    Search(String),
+    Search(SearchQuery),
+    Open(usize),
+    Retry(usize),
+    Find(SearchQuery),
+    Replace(SearchQuery, String),
     List,
     MultiLine,
     Edit,
@@ -10,17 +28,569 @@ pub enum Command {
     EditCancel,
     Save(Option<String>),
     Load(String),
-    ListFiles,
+    Preview(String),
+    ListFiles(Vec<MetaFilter>),
     NewNote(bool),
+    NewFromTemplate(String, Vec<(String, String)>),
+    ListTemplates,
+    SaveTemplate(String),
     Tag(String),
-    ListTags,
+    Untag(String),
+    TagRename(String, String),
+    TagMerge(String, String),
+    ListTags(bool),
     ListByTag(String),
+    Meta(MetaAction),
     Stats,
-    Help,
+    Export(ExportOptions),
+    Quick(QuickOptions),
+    AppendStdin(Option<String>),
+    Vault(Option<String>),
+    Help(Option<String>),
     Quit,
+    Source(String),
+    Alias(Option<(String, String)>),
+    Macro(String, String),
     Invalid(String),
+    Usage(&'static str, String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgSpec {
+    None,
+    Optional(&'static str),
+    Required(&'static str),
+}
+
+impl ArgSpec {
+    pub fn usage(&self) -> &'static str {
+        match self {
+            ArgSpec::None => "",
+            ArgSpec::Optional(usage) | ArgSpec::Required(usage) => usage,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Completion {
+    None,
+    Notes,
+    Tags,
+    Templates,
+    Vaults,
+}
+
+pub struct Invocation<'a> {
+    pub spec: &'static CommandSpec,
+    pub args: &'a [String],
+    pub raw: &'a str,
+    pub edit_mode: bool,
+}
+
+impl Invocation<'_> {
+    fn usage_error(&self) -> String {
+        format!("usage: {}", self.spec.usage())
+    }
+
+    fn arg(&self, idx: usize) -> String {
+        self.args[idx].clone()
+    }
+}
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub args: ArgSpec,
+    pub help: &'static str,
+    pub parent: Option<&'static str>,
+    pub options: &'static [(&'static str, &'static str)],
+    pub complete: Completion,
+    pub handler: fn(&Invocation) -> Result<Command, String>,
+}
+
+impl CommandSpec {
+    pub fn usage(&self) -> String {
+        match self.args {
+            ArgSpec::None => format!(":{}", self.name),
+            args => format!(":{} {}", self.name, args.usage()),
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+}
+
+const NO_OPTIONS: &[(&str, &str)] = &[];
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "help",
+        aliases: &["h"],
+        args: ArgSpec::Optional("[command]"),
+        help: "show all commands, or details for one",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| Ok(Command::Help(inv.args.first().map(|name| name.trim_start_matches(':').to_string()))),
+    },
+    CommandSpec {
+        name: "alias",
+        aliases: &[],
+        args: ArgSpec::Optional("[name = command]"),
+        help: "list aliases and macros, or define an alias",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| {
+            if inv.raw.is_empty() {
+                return Ok(Command::Alias(None));
+            }
+            Aliases::parse_definition(inv.raw).map(|definition| Command::Alias(Some(definition)))
+        },
+    },
+    CommandSpec {
+        name: "macro",
+        aliases: &[],
+        args: ArgSpec::Required("[name = cmd; cmd; ...]"),
+        help: "define a macro that runs several commands",
+        parent: Some("alias"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| Aliases::parse_definition(inv.raw).map(|(name, steps)| Command::Macro(name, steps)),
+    },
+    CommandSpec {
+        name: "source",
+        aliases: &[],
+        args: ArgSpec::Required("[file]"),
+        help: "run the commands in a file",
+        parent: Some("alias"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| match inv.args {
+            [path] => Ok(Command::Source(path.clone())),
+            _ => Err(inv.usage_error()),
+        },
+    },
+    CommandSpec {
+        name: "quit",
+        aliases: &["q"],
+        args: ArgSpec::None,
+        help: "exit rustynotes (saves unsaved changes)",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::Quit),
+    },
+    CommandSpec {
+        name: "n",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "new note, warns about unsaved changes",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::NewNote(false)),
+    },
+    CommandSpec {
+        name: "n!",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "new note, discards unsaved changes",
+        parent: Some("n"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::NewNote(true)),
+    },
+    CommandSpec {
+        name: "new",
+        aliases: &[],
+        args: ArgSpec::Required("[template]"),
+        help: "new note from template",
+        parent: None,
+        options: &[
+            ("--name [note]", "note name, fills {{name}}"),
+            ("--key [value]", "fill {{key}}, prompts if missing"),
+            ("variables: a, b", "frontmatter list that enables bare $a, $b"),
+            ("$$", "a literal $ in the template"),
+        ],
+        complete: Completion::Templates,
+        handler: |inv| {
+            if inv.args[0].starts_with("--") {
+                return Err("template name required".to_string());
+            }
+            let values = CommandParser::parse_key_values(&inv.args[1..])?;
+            Ok(Command::NewFromTemplate(inv.arg(0), values))
+        },
+    },
+    CommandSpec {
+        name: "templates",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "list templates",
+        parent: Some("new"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::ListTemplates),
+    },
+    CommandSpec {
+        name: "save-template",
+        aliases: &[],
+        args: ArgSpec::Required("[name]"),
+        help: "save current note as template",
+        parent: Some("new"),
+        options: NO_OPTIONS,
+        complete: Completion::Templates,
+        handler: |inv| Ok(Command::SaveTemplate(inv.arg(0))),
+    },
+    CommandSpec {
+        name: "quick",
+        aliases: &[],
+        args: ArgSpec::Required("\"text\""),
+        help: "capture text without touching current note",
+        parent: None,
+        options: &[
+            ("--save [name]", "new note (--timestamp prefixes the date)"),
+            ("--add [note]", "append to an existing note"),
+            ("--tag a,b", "tags for the note"),
+            ("--template [name]", "create the note from a template"),
+            ("--fence [lang]", "wrap the text in a code fence"),
+        ],
+        complete: Completion::None,
+        handler: |inv| QuickOptions::parse(inv.args).map(Command::Quick),
+    },
+    CommandSpec {
+        name: "append-stdin",
+        aliases: &[],
+        args: ArgSpec::Optional("[--fence lang]"),
+        help: "append stdin (until eof) to current note",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| match inv.args {
+            [] => Ok(Command::AppendStdin(None)),
+            [flag, lang] if flag == "--fence" => Ok(Command::AppendStdin(Some(lang.clone()))),
+            _ => Err(inv.usage_error()),
+        },
+    },
+    CommandSpec {
+        name: "save",
+        aliases: &[],
+        args: ArgSpec::Optional("[name]"),
+        help: "save note (with optional name)",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::Notes,
+        handler: |inv| {
+            if inv.edit_mode {
+                Ok(Command::EditSave)
+            } else if inv.args.is_empty() {
+                Ok(Command::Save(None))
+            } else {
+                Ok(Command::Save(Some(inv.args.join("_"))))
+            }
+        },
+    },
+    CommandSpec {
+        name: "load",
+        aliases: &[],
+        args: ArgSpec::Required("[N|name]"),
+        help: "load note (N from last :ls or :grep)",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::Notes,
+        handler: |inv| Ok(Command::Load(inv.arg(0))),
+    },
+    CommandSpec {
+        name: "ls",
+        aliases: &["files"],
+        args: ArgSpec::Optional("[--where key=value]"),
+        help: "list saved notes",
+        parent: None,
+        options: &[("--where key=value", "only notes whose metadata matches")],
+        complete: Completion::None,
+        handler: |inv| CommandParser::parse_where(inv.args).map(Command::ListFiles),
+    },
+    CommandSpec {
+        name: "preview",
+        aliases: &[],
+        args: ArgSpec::Required("[N|name]"),
+        help: "show a saved note without loading it",
+        parent: Some("ls"),
+        options: NO_OPTIONS,
+        complete: Completion::Notes,
+        handler: |inv| Ok(Command::Preview(inv.arg(0))),
+    },
+    CommandSpec {
+        name: "list",
+        aliases: &["l"],
+        args: ArgSpec::None,
+        help: "show current note",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::List),
+    },
+    CommandSpec {
+        name: "vault",
+        aliases: &[],
+        args: ArgSpec::Optional("[name]"),
+        help: "list vaults or switch to one",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::Vaults,
+        handler: |inv| Ok(Command::Vault(inv.args.first().cloned())),
+    },
+    CommandSpec {
+        name: "stats",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "show note statistics",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::Stats),
+    },
+    CommandSpec {
+        name: "export",
+        aliases: &[],
+        args: ArgSpec::Optional("[options]"),
+        help: "export notes (--tag --search --from --to)",
+        parent: None,
+        options: &[
+            ("--format [fmt]", "md, html, json or pdf (default md)"),
+            ("--out [path]", "output file (default ~/.notes/exports)"),
+        ],
+        complete: Completion::None,
+        handler: |inv| ExportOptions::parse(inv.args).map(Command::Export),
+    },
+    CommandSpec {
+        name: "meta",
+        aliases: &[],
+        args: ArgSpec::Optional("[get|set|unset] [key] [value]"),
+        help: "show metadata of current note",
+        parent: None,
+        options: &[
+            ("get [key]", "show one field"),
+            ("set [key] [value]", "set a field ([a, b] for lists)"),
+            ("unset [key]", "remove a field"),
+        ],
+        complete: Completion::None,
+        handler: |inv| CommandParser::parse_meta(inv.args).map(Command::Meta),
+    },
+    CommandSpec {
+        name: "tag",
+        aliases: &[],
+        args: ArgSpec::Required("[name]"),
+        help: "add tag to current note",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::Tags,
+        handler: |inv| Ok(Command::Tag(inv.arg(0))),
+    },
+    CommandSpec {
+        name: "untag",
+        aliases: &[],
+        args: ArgSpec::Required("[name]"),
+        help: "remove tag from current note",
+        parent: Some("tag"),
+        options: NO_OPTIONS,
+        complete: Completion::Tags,
+        handler: |inv| Ok(Command::Untag(inv.arg(0))),
+    },
+    CommandSpec {
+        name: "tags",
+        aliases: &[],
+        args: ArgSpec::Optional("[--tree]"),
+        help: "list all tags (--tree to nest a/b/c tags)",
+        parent: Some("tag"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| match inv.args {
+            [] => Ok(Command::ListTags(false)),
+            [flag] if flag == "--tree" => Ok(Command::ListTags(true)),
+            _ => Err(inv.usage_error()),
+        },
+    },
+    CommandSpec {
+        name: "tag-rename",
+        aliases: &[],
+        args: ArgSpec::Required("[old] [new]"),
+        help: "rename a tag in every note",
+        parent: Some("tag"),
+        options: NO_OPTIONS,
+        complete: Completion::Tags,
+        handler: |inv| match inv.args {
+            [old, new] => Ok(Command::TagRename(old.clone(), new.clone())),
+            _ => Err(inv.usage_error()),
+        },
+    },
+    CommandSpec {
+        name: "tag-merge",
+        aliases: &[],
+        args: ArgSpec::Required("[a] [b]"),
+        help: "fold tag a into tag b in every note",
+        parent: Some("tag"),
+        options: NO_OPTIONS,
+        complete: Completion::Tags,
+        handler: |inv| match inv.args {
+            [from, into] => Ok(Command::TagMerge(from.clone(), into.clone())),
+            _ => Err(inv.usage_error()),
+        },
+    },
+    CommandSpec {
+        name: "tagged",
+        aliases: &[],
+        args: ArgSpec::Required("[tag]"),
+        help: "list notes with tag or any tag below it",
+        parent: Some("tag"),
+        options: NO_OPTIONS,
+        complete: Completion::Tags,
+        handler: |inv| Ok(Command::ListByTag(inv.arg(0))),
+    },
+    CommandSpec {
+        name: "search",
+        aliases: &[],
+        args: ArgSpec::Required("[keyword]"),
+        help: "search for keyword",
+        parent: None,
+        options: &[
+            ("--regex -i -w", "regex / ignore case / whole word"),
+            ("--not [term]", "skip lines containing term"),
+            ("-C/-A/-B N", "show N lines of context (--preview = -C 2)"),
+            ("--urls --ips", "list urls / ip addresses"),
+            ("--emails", "list email addresses"),
+            ("--headers", "list http request/response lines"),
+            ("--code [lang]", "list fenced code blocks"),
+        ],
+        complete: Completion::None,
+        handler: |inv| SearchQuery::parse(inv.args).map(Command::Search),
+    },
+    CommandSpec {
+        name: "grep",
+        aliases: &[],
+        args: ArgSpec::Required("[keyword]"),
+        help: "search all saved notes (:search --all)",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| {
+            let mut query = SearchQuery::parse(inv.args)?;
+            query.all_notes = true;
+            Ok(Command::Search(query))
+        },
+    },
+    CommandSpec {
+        name: "retry",
+        aliases: &[],
+        args: ArgSpec::Optional("[N]"),
+        help: "search again with suggestion N",
+        parent: Some("grep"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| match inv.args.first().map(|n| n.parse::<usize>()) {
+            None => Ok(Command::Retry(1)),
+            Some(Ok(num)) => Ok(Command::Retry(num)),
+            Some(Err(_)) => Err("invalid suggestion number".to_string()),
+        },
+    },
+    CommandSpec {
+        name: "open",
+        aliases: &[],
+        args: ArgSpec::Required("N"),
+        help: "load note N from last results",
+        parent: Some("grep"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| {
+            inv.args[0]
+                .parse::<usize>()
+                .map(Command::Open)
+                .map_err(|_| "invalid result number".to_string())
+        },
+    },
+    CommandSpec {
+        name: "find",
+        aliases: &[],
+        args: ArgSpec::Required("[term]"),
+        help: "find term in note or edit buffer",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| match SearchQuery::parse(inv.args)? {
+            query if query.extract.is_none() => Ok(Command::Find(query)),
+            _ => Err("content filters only work with :search".to_string()),
+        },
+    },
+    CommandSpec {
+        name: "replace",
+        aliases: &[],
+        args: ArgSpec::Required("[a] [b]"),
+        help: "replace a with b (-i -w --regex, $1 or ${1} groups)",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| match SearchQuery::parse_flags(inv.args)? {
+            (mut query, terms) if terms.len() == 2 && query.extract.is_none() => {
+                query.term = terms[0].clone();
+                Ok(Command::Replace(query, terms[1].clone()))
+            }
+            _ => Err("usage: :replace [flags] <term> <replacement>".to_string()),
+        },
+    },
+    CommandSpec {
+        name: "ml",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "start/end multi-line input",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::MultiLine),
+    },
+    CommandSpec {
+        name: "edit",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "start edit mode (:save keeps changes)",
+        parent: None,
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |_| Ok(Command::Edit),
+    },
+    CommandSpec {
+        name: "line",
+        aliases: &[],
+        args: ArgSpec::Required("N"),
+        help: "select line to edit",
+        parent: Some("edit"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| {
+            inv.args[0]
+                .parse::<usize>()
+                .map(Command::EditLine)
+                .map_err(|_| "invalid line number".to_string())
+        },
+    },
+    CommandSpec {
+        name: "cancel",
+        aliases: &[],
+        args: ArgSpec::None,
+        help: "discard edit mode changes",
+        parent: Some("edit"),
+        options: NO_OPTIONS,
+        complete: Completion::None,
+        handler: |inv| {
+            if inv.edit_mode {
+                Ok(Command::EditCancel)
+            } else {
+                Err("not in edit mode".to_string())
+            }
+        },
+    },
+];
+
 pub struct CommandParser;
 
 impl CommandParser {
@@ -34,71 +604,129 @@ impl CommandParser {
             return Command::Write(input.to_string());
         }
 
-        if input.starts_with(':') {
-            let parts: Vec<&str> = input[1..].split_whitespace().collect();
-            match parts.get(0).map(|&s| s) {
-                Some("h") | Some("help") => Command::Help,
-                Some("q") | Some("quit") => Command::Quit,
-                Some("l") | Some("list") => Command::List,
-                Some("ls") | Some("files") => Command::ListFiles,
-                Some("edit") => Command::Edit,
-                Some("line") => {
-                    if parts.len() > 1 {
-                        if let Ok(num) = parts[1].parse::<usize>() {
-                            Command::EditLine(num)
-                        } else {
-                            Command::Invalid("invalid line number".to_string())
-                        }
-                    } else {
-                        Command::Invalid("line number required..".to_string())
-                    }
-                }
-                Some("save") if edit_mode => Command::EditSave,
-                Some("cancel") if edit_mode => Command::EditCancel,
-                Some("ml") => Command::MultiLine,
-                Some("n") => Command::NewNote(false),
-                Some("n!") => Command::NewNote(true),
-                Some("stats") => Command::Stats,
-                Some("tag") => {
-                    if parts.len() > 1 {
-                        Command::Tag(parts[1].to_string())
-                    } else {
-                        Command::Invalid("tag name required".to_string())
-                    }
+        let Some(rest) = input.strip_prefix(':') else {
+            return Command::Write(input.to_string());
+        };
+
+        let args = Self::split_args(rest);
+        let Some(spec) = args.first().and_then(|name| Self::find(name)) else {
+            return Command::Invalid(input.to_string());
+        };
+
+        let raw = rest
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, raw)| raw.trim());
+        let invocation = Invocation {
+            spec,
+            args: &args[1..],
+            raw,
+            edit_mode,
+        };
+        let result = match (spec.args, invocation.args.is_empty()) {
+            (ArgSpec::None, false) => Err(format!("{} takes no arguments", spec.usage())),
+            (ArgSpec::Required(_), true) => Err(invocation.usage_error()),
+            _ => (spec.handler)(&invocation),
+        };
+        result.unwrap_or_else(|e| Command::Usage(spec.name, e))
+    }
+
+    pub fn find(name: &str) -> Option<&'static CommandSpec> {
+        COMMANDS.iter().find(|spec| spec.matches(name))
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        let mut names: Vec<&str> = COMMANDS
+            .iter()
+            .flat_map(|spec| std::iter::once(spec.name).chain(spec.aliases.iter().copied()))
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn parse_where(args: &[String]) -> Result<Vec<MetaFilter>, String> {
+        let mut filters = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--where" => match iter.next() {
+                    Some(expr) => filters.push(MetaFilter::parse(expr)?),
+                    None => return Err("--where requires key=value".to_string()),
+                },
+                _ => return Err(format!("unknown ls option: {}", arg)),
+            }
+        }
+        Ok(filters)
+    }
+
+    fn parse_meta(args: &[String]) -> Result<MetaAction, String> {
+        match args {
+            [] => Ok(MetaAction::Show),
+            [action, key] if action == "get" => Ok(MetaAction::Get(key.to_lowercase())),
+            [action, key] if action == "unset" => Ok(MetaAction::Unset(key.to_lowercase())),
+            [action, key, value @ ..] if action == "set" && !value.is_empty() => {
+                Ok(MetaAction::Set(key.to_lowercase(), value.join(" ")))
+            }
+            _ => Err("usage: :meta [get key | set key value | unset key]".to_string()),
+        }
+    }
+
+    pub fn parse_key_values(args: &[String]) -> Result<Vec<(String, String)>, String> {
+        let mut values = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let key = match arg.strip_prefix("--") {
+                Some(key) if !key.is_empty() => key,
+                _ => return Err(format!("expected --key value, got: {}", arg)),
+            };
+            if let Some((key, value)) = key.split_once('=') {
+                values.push((key.to_lowercase(), value.to_string()));
+            } else {
+                match iter.next() {
+                    Some(value) => values.push((key.to_lowercase(), value.clone())),
+                    None => return Err(format!("missing value for --{}", key)),
                 }
-                Some("tags") => Command::ListTags,
-                Some("tagged") => {
-                    if parts.len() > 1 {
-                        Command::ListByTag(parts[1].to_string())
-                    } else {
-                        Command::Invalid("tag name required".to_string())
-                    }
+            }
+        }
+        Ok(values)
+    }
+
+    pub fn split_args(input: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut current = String::new();
+        let mut quote: Option<char> = None;
+        let mut in_arg = false;
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(q) if c == '\\' && chars.peek() == Some(&q) => {
+                    current.push(q);
+                    chars.next();
                 }
-                Some("search") => {
-                    if parts.len() > 1 {
-                        Command::Search(parts[1..].join(" "))
-                    } else {
-                        Command::Invalid("search term required".to_string())
-                    }
+                Some(_) => current.push(c),
+                None if c == '"' || c == '\'' => {
+                    quote = Some(c);
+                    in_arg = true;
                 }
-                Some("save") => {
-                    if parts.len() > 1 {
-                        Command::Save(Some(parts[1..].join("_")))
-                    } else {
-                        Command::Save(None)
+                None if c.is_whitespace() => {
+                    if in_arg {
+                        args.push(std::mem::take(&mut current));
+                        in_arg = false;
                     }
                 }
-                Some("load") => {
-                    if parts.len() > 1 {
-                        Command::Load(parts[1].to_string())
-                    } else {
-                        Command::Invalid("filename required".to_string())
-                    }
+                None => {
+                    current.push(c);
+                    in_arg = true;
                 }
-                _ => Command::Invalid(input.to_string()),
             }
-        } else {
-            Command::Write(input.to_string())
         }
+        if in_arg {
+            args.push(current);
+        }
+        args
     }
-}
\ No newline at end of file
+}
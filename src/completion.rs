@@ -0,0 +1,167 @@
+use crate::commands::{ArgSpec, CommandParser, Completion};
+use crate::file_ops::FileOperations;
+use crate::templates::TemplateManager;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::{Hint, Hinter};
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+pub struct CommandHint {
+    display: String,
+    completion: bool,
+}
+
+impl Hint for CommandHint {
+    fn display(&self) -> &str {
+        &self.display
+    }
+
+    fn completion(&self) -> Option<&str> {
+        self.completion.then_some(self.display.as_str())
+    }
+}
+
+pub struct NoteHelper {
+    pub notes_dir: PathBuf,
+    pub current_tags: Vec<String>,
+    pub vaults: Vec<String>,
+    pub aliases: Vec<String>,
+    pub in_multi_line: bool,
+}
+
+impl NoteHelper {
+    pub fn new(notes_dir: PathBuf) -> Self {
+        NoteHelper {
+            notes_dir,
+            current_tags: Vec::new(),
+            vaults: Vec::new(),
+            aliases: Vec::new(),
+            in_multi_line: false,
+        }
+    }
+
+    fn command_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = CommandParser::names().into_iter().map(str::to_string).collect();
+        names.extend(self.aliases.iter().cloned());
+        names.sort();
+        names
+    }
+
+    fn note_names(&self) -> Vec<String> {
+        FileOperations::list_saved_notes(&self.notes_dir)
+            .map(|notes| {
+                notes
+                    .into_iter()
+                    .map(|(filename, _, _)| filename.trim_end_matches(".md").to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn tag_names(&self) -> Vec<String> {
+        let mut tags: Vec<String> = FileOperations::get_all_tags(&self.notes_dir, None, &self.current_tags)
+            .map(|(tags, _)| tags.into_iter().collect())
+            .unwrap_or_default();
+        tags.sort();
+        tags
+    }
+
+    fn template_names(&self) -> Vec<String> {
+        let mut names = TemplateManager::builtin_names();
+        names.extend(TemplateManager::user_names(&self.notes_dir).unwrap_or_default());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn pairs<S: AsRef<str>>(words: &[S], prefix: &str) -> Vec<Pair> {
+        words
+            .iter()
+            .map(|word| word.as_ref())
+            .filter(|word| word.starts_with(prefix))
+            .map(|word| Pair {
+                display: word.to_string(),
+                replacement: word.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl Completer for NoteHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let rest = match before.strip_prefix(':') {
+            Some(rest) if !self.in_multi_line => rest,
+            _ => return Ok((pos, Vec::new())),
+        };
+
+        let Some((cmd, _)) = rest.split_once(char::is_whitespace) else {
+            return Ok((1, Self::pairs(&self.command_names(), rest)));
+        };
+
+        let word = before.rsplit(char::is_whitespace).next().unwrap_or("");
+        let words = match CommandParser::find(cmd).map(|spec| spec.complete) {
+            Some(Completion::Notes) => self.note_names(),
+            Some(Completion::Tags) => self.tag_names(),
+            Some(Completion::Templates) => self.template_names(),
+            Some(Completion::Vaults) => self.vaults.clone(),
+            Some(Completion::None) | None => return Ok((pos, Vec::new())),
+        };
+        Ok((pos - word.len(), Self::pairs(&words, word)))
+    }
+}
+
+impl Hinter for NoteHelper {
+    type Hint = CommandHint;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<CommandHint> {
+        if pos < line.len() || self.in_multi_line {
+            return None;
+        }
+        let rest = line.strip_prefix(':').filter(|rest| !rest.is_empty())?;
+
+        let args_hint = |cmd: &str, sep: &str| {
+            CommandParser::find(cmd)
+                .filter(|spec| spec.args != ArgSpec::None)
+                .map(|spec| CommandHint {
+                    display: format!("{}{}", sep, spec.args.usage()),
+                    completion: false,
+                })
+        };
+
+        match rest.split_once(' ') {
+            None if CommandParser::find(rest).is_some() => args_hint(rest, " "),
+            None => {
+                let names: Vec<String> = self
+                    .command_names()
+                    .into_iter()
+                    .filter(|name| name.starts_with(rest))
+                    .collect();
+                match names.as_slice() {
+                    [name] => Some(CommandHint {
+                        display: name[rest.len()..].to_string(),
+                        completion: true,
+                    }),
+                    _ => None,
+                }
+            }
+            Some((cmd, "")) => args_hint(cmd, ""),
+            Some(_) => None,
+        }
+    }
+}
+
+impl Highlighter for NoteHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("\x1b[2m{}\x1b[0m", hint))
+    }
+}
+
+impl Validator for NoteHelper {}
+
+impl Helper for NoteHelper {}
@@ -0,0 +1,113 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_HISTORY_SIZE: usize = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub notes_dir: Option<String>,
+    pub default_vault: Option<String>,
+    pub history_size: Option<usize>,
+    pub vaults: BTreeMap<String, String>,
+    pub aliases: BTreeMap<String, String>,
+    pub macros: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rustynotes").join("config.toml"))
+    }
+
+    pub fn load() -> io::Result<Config> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> io::Result<Config> {
+        let raw = fs::read_to_string(path)?;
+        toml::from_str(&raw).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e.message()),
+            )
+        })
+    }
+
+    pub fn expand_path(path: &str) -> PathBuf {
+        match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map_or_else(|| PathBuf::from(path), |home| home.join(rest)),
+            None if path == "~" => dirs::home_dir().unwrap_or_else(|| PathBuf::from(path)),
+            None => PathBuf::from(path),
+        }
+    }
+
+    pub fn default_notes_dir(&self) -> Option<PathBuf> {
+        match &self.notes_dir {
+            Some(dir) => Some(Self::expand_path(dir)),
+            None => dirs::home_dir().map(|home| home.join(".notes")),
+        }
+    }
+
+    pub fn vault_dirs(&self) -> BTreeMap<String, PathBuf> {
+        self.vaults
+            .iter()
+            .map(|(name, dir)| (name.clone(), Self::expand_path(dir)))
+            .collect()
+    }
+
+    pub fn history_size(&self) -> usize {
+        std::env::var("RUSTYNOTES_HISTORY_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .or(self.history_size)
+            .unwrap_or(DEFAULT_HISTORY_SIZE)
+    }
+
+    pub fn resolve_notes_dir(
+        &self,
+        dir_flag: Option<&str>,
+        vault_flag: Option<&str>,
+    ) -> io::Result<(PathBuf, Option<String>)> {
+        if let Some(dir) = dir_flag {
+            return Ok((Self::expand_path(dir), None));
+        }
+
+        if vault_flag.is_none() {
+            if let Ok(dir) = std::env::var("RUSTYNOTES_DIR") {
+                if !dir.is_empty() {
+                    return Ok((Self::expand_path(&dir), None));
+                }
+            }
+        }
+
+        if let Some(vault) = vault_flag.or(self.default_vault.as_deref()) {
+            return match self.vaults.get(vault) {
+                Some(dir) => Ok((Self::expand_path(dir), Some(vault.to_string()))),
+                None if vault == "default" => self.fallback_notes_dir(),
+                None => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("unknown vault: {}", vault),
+                )),
+            };
+        }
+
+        self.fallback_notes_dir()
+    }
+
+    fn fallback_notes_dir(&self) -> io::Result<(PathBuf, Option<String>)> {
+        self.default_notes_dir()
+            .map(|dir| (dir, None))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "could not find home directory, set RUSTYNOTES_DIR or use --dir",
+                )
+            })
+    }
+}
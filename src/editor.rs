@@ -1,10 +1,21 @@
-use crate::commands::Command;
+use crate::aliases::Aliases;
+use crate::commands::{Command, CommandParser, CommandSpec, MetaAction, COMMANDS};
+use crate::config::Config;
+use crate::export::Exporter;
+use crate::extract::{ContentExtractor, Extracted, Extractor};
 use crate::file_ops::FileOperations;
+use crate::frontmatter::{FieldValue, Frontmatter, MetaFilter};
+use crate::quick::{QuickCapture, QuickOptions};
+use crate::search::{Matcher, SearchQuery, Searcher};
+use crate::session::Session;
 use crate::stats::{StatsCalculator, NoteStats};
+use crate::templates::{Template, TemplateManager, TemplateSource};
 use rustyline::error::ReadlineError;
 use rustyline::Editor as LineEditor;
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct Editor {
     pub content: String,
@@ -13,17 +24,26 @@ pub struct Editor {
     pub current_block: String,
     pub current_file: Option<PathBuf>,
     pub notes_dir: PathBuf,
+    pub default_dir: PathBuf,
+    pub vaults: BTreeMap<String, PathBuf>,
+    pub current_vault: Option<String>,
     pub current_tags: Vec<String>,
+    pub current_meta: Frontmatter,
     pub stats_cache: Option<NoteStats>,
     pub stats_dirty: bool,
     pub edit_mode: bool,
     pub edit_buffer: Vec<String>,
+    pub last_listing: Vec<String>,
+    pub last_query: Option<SearchQuery>,
+    pub suggestions: Vec<String>,
+    pub aliases: Aliases,
+    pub sourcing: Vec<PathBuf>,
+    pub script_location: Option<String>,
+    pub failed: bool,
 }
 
 impl Editor {
-    pub fn new() -> io::Result<Self> {
-        let home = dirs::home_dir().expect("could not find home directory");
-        let notes_dir = home.join(".notes");
+    pub fn new(notes_dir: PathBuf) -> io::Result<Self> {
         std::fs::create_dir_all(&notes_dir)?;
 
         Ok(Editor {
@@ -32,12 +52,23 @@ impl Editor {
             in_multi_line: false,
             current_block: String::new(),
             current_file: None,
+            default_dir: notes_dir.clone(),
             notes_dir,
+            vaults: BTreeMap::new(),
+            current_vault: None,
             current_tags: Vec::new(),
+            current_meta: Frontmatter::default(),
             stats_cache: None,
             stats_dirty: true,
             edit_mode: false,
             edit_buffer: Vec::new(),
+            last_listing: Vec::new(),
+            last_query: None,
+            suggestions: Vec::new(),
+            aliases: Aliases::default(),
+            sourcing: Vec::new(),
+            script_location: None,
+            failed: false,
         })
     }
 
@@ -47,10 +78,11 @@ impl Editor {
     }
 
     pub fn load_file(&mut self, name: &str) -> io::Result<()> {
-        match FileOperations::load_file(&self.notes_dir, name) {
-            Ok((content, tags, path)) => {
+        match FileOperations::load_note(&self.notes_dir, name) {
+            Ok((content, meta, path)) => {
                 self.content = content;
-                self.current_tags = tags;
+                self.current_tags = meta.tags();
+                self.current_meta = meta;
                 self.current_file = Some(path.clone());
                 self.modified = false;
                 self.invalidate_stats_cache();
@@ -60,7 +92,7 @@ impl Editor {
                 }
             }
             Err(e) => {
-                println!("[-] {}", e);
+                self.fail(e);
             }
         }
         Ok(())
@@ -98,6 +130,140 @@ impl Editor {
         }
     }
 
+    pub fn remove_tag(&mut self, tag: &str) {
+        let tag = tag.to_lowercase();
+        if self.current_tags.contains(&tag) {
+            self.current_tags.retain(|t| *t != tag);
+            self.modified = true;
+            self.invalidate_stats_cache();
+            println!("[+] removed tag: {}", tag);
+        } else {
+            println!("[-] note is not tagged: {}", tag);
+        }
+    }
+
+    fn retag(&mut self, old: &str, new: &str, merge: bool) -> io::Result<()> {
+        let (old, new) = (
+            old.trim_matches('/').to_lowercase(),
+            new.trim_matches('/').to_lowercase(),
+        );
+        if old == new {
+            self.fail(format!("nothing to do, both tags are '{}'", old));
+            return Ok(());
+        }
+
+        let (all_tags, _) = FileOperations::get_all_tags(&self.notes_dir, None, &self.current_tags)?;
+        if !all_tags.contains(&old) {
+            self.fail(format!("no notes tagged '{}'", old));
+            return Ok(());
+        }
+        if !merge && all_tags.contains(&new) {
+            self.fail(format!("tag '{}' already exists", new));
+            println!("    use :tag-merge {} {} to fold one into the other", old, new);
+            return Ok(());
+        }
+
+        let mut notes = FileOperations::find_notes_by_tag(&self.notes_dir, &old)?;
+        notes.sort();
+        let current_name = self
+            .current_file
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned());
+        let unsaved_current = self.current_tags.iter().any(|tag| FileOperations::tag_matches(tag, &old))
+            && !current_name.as_ref().is_some_and(|name| notes.contains(name));
+
+        let action = if merge { "merge tag" } else { "rename tag" };
+        println!("\n{} '{}' -> '{}':", action, old, new);
+        println!("{}", "=".repeat(40));
+        for note in &notes {
+            println!("  {}", note);
+        }
+        if unsaved_current {
+            println!("  [current note, not saved yet]");
+        }
+        println!("{}", "=".repeat(40));
+        println!("{} note(s) will change", notes.len() + usize::from(unsaved_current));
+
+        if !self.confirm("apply? [y/N] ")? {
+            println!("[-] cancelled, nothing changed");
+            return Ok(());
+        }
+
+        let changed = FileOperations::retag_notes(&self.notes_dir, &notes, &old, &new)?;
+        self.current_tags = FileOperations::rename_tag(&self.current_tags, &old, Some(&new));
+        if unsaved_current {
+            self.modified = true;
+        }
+        self.invalidate_stats_cache();
+        println!(
+            "[+] {} '{}' -> '{}' in {} note(s)",
+            if merge { "merged" } else { "renamed" },
+            old,
+            new,
+            changed + usize::from(unsaved_current)
+        );
+        Ok(())
+    }
+
+    pub fn note_meta(&self) -> Frontmatter {
+        let mut meta = self.current_meta.clone();
+        meta.set_tags(&self.current_tags);
+        meta
+    }
+
+    fn run_meta(&mut self, action: MetaAction) {
+        match action {
+            MetaAction::Show => {
+                let meta = self.note_meta();
+                if meta.is_empty() {
+                    println!("[-] no metadata, use :meta set [key] [value]");
+                    return;
+                }
+                println!("\nmetadata:");
+                println!("{}", "=".repeat(40));
+                let width = meta.fields().map(|(key, _)| key.len()).max().unwrap_or(0);
+                for (key, value) in meta.fields() {
+                    println!("  {:<width$}  {}", key, value.display(), width = width);
+                }
+                println!("{}", "=".repeat(40));
+            }
+            MetaAction::Get(key) => match self.note_meta().get(&key) {
+                Some(value) => println!("{}", value.display()),
+                None => self.fail(format!("no field: {}", key)),
+            },
+            MetaAction::Set(key, value) => {
+                if !Frontmatter::valid_key(&key) {
+                    self.fail(format!("invalid field name: {}", key));
+                    return;
+                }
+                let value = Frontmatter::parse_value(&key, &value);
+                if key == "tags" {
+                    self.current_tags = match &value {
+                        FieldValue::List(tags) => tags.iter().map(|tag| tag.to_lowercase()).collect(),
+                        FieldValue::Scalar(tag) => vec![tag.to_lowercase()],
+                    };
+                }
+                println!("[+] {}: {}", key, value.display());
+                self.current_meta.set(&key, value);
+                self.modified = true;
+                self.invalidate_stats_cache();
+            }
+            MetaAction::Unset(key) => {
+                let removed = self.current_meta.remove(&key);
+                if key == "tags" && !self.current_tags.is_empty() {
+                    self.current_tags.clear();
+                } else if !removed {
+                    self.fail(format!("no field: {}", key));
+                    return;
+                }
+                println!("[+] removed {}", key);
+                self.modified = true;
+                self.invalidate_stats_cache();
+            }
+        }
+    }
+
     pub fn save_current(&mut self) -> io::Result<()> {
         if !self.modified {
             println!("[-] no changes to save");
@@ -109,13 +275,15 @@ impl Editor {
             .and_then(|stem| stem.to_str())
             .map(|s| s.to_string());
 
-        let file_path = FileOperations::save_file(
+        let meta = self.note_meta();
+        let file_path = FileOperations::save_note(
             &self.notes_dir,
             &FileOperations::format_content(&self.content),
-            &self.current_tags,
+            &meta,
             filename.as_deref(),
         )?;
 
+        self.current_meta = meta;
         self.current_file = Some(file_path.clone());
         self.modified = false;
         self.invalidate_stats_cache();
@@ -150,7 +318,7 @@ impl Editor {
 
     pub fn edit_line(&mut self, line_num: usize) -> io::Result<()> {
         if line_num == 0 || line_num > self.edit_buffer.len() {
-            println!("[-] invalid line");
+            self.fail("invalid line");
             return Ok(());
         }
         let line_idx = line_num - 1;
@@ -161,7 +329,7 @@ impl Editor {
         io::stdout().flush()?;
 
         let mut line_editor = LineEditor::<(), _>::new()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            .map_err(io::Error::other)?;
         line_editor.set_helper(Some(()));
         match line_editor.readline_with_initial("", (current_line, "")) {
             Ok(new_line) => {
@@ -198,6 +366,146 @@ impl Editor {
         println!("[-] changed your mind, huh?");
     }
 
+    pub fn run_line(&mut self, line: &str) -> io::Result<bool> {
+        self.run_script_line(line, None)
+    }
+
+    fn run_script_line(&mut self, line: &str, location: Option<&str>) -> io::Result<bool> {
+        let outer = std::mem::replace(&mut self.script_location, location.map(str::to_string));
+        let result = self.run_steps(line);
+        self.script_location = outer;
+        result
+    }
+
+    fn run_steps(&mut self, line: &str) -> io::Result<bool> {
+        let lines = if self.in_multi_line {
+            vec![line.to_string()]
+        } else {
+            match self.aliases.expand(line) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    self.fail(e);
+                    return Ok(true);
+                }
+            }
+        };
+
+        let is_macro = lines.len() > 1;
+        for line in lines {
+            let command = CommandParser::parse(&line, self.in_multi_line, self.edit_mode);
+            self.failed = false;
+            if !self.execute_command(command)? {
+                return Ok(false);
+            }
+            if self.failed {
+                if is_macro {
+                    println!("    macro stopped at {}", line);
+                }
+                break;
+            }
+        }
+        Ok(true)
+    }
+
+    fn fail(&mut self, message: impl std::fmt::Display) {
+        self.failed = true;
+        match &self.script_location {
+            Some(location) => println!("[-] {}: {}", location, message),
+            None => println!("[-] {}", message),
+        }
+    }
+
+    pub fn source_file(&mut self, path: &Path) -> io::Result<bool> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if self.sourcing.contains(&canonical) {
+            self.fail(format!("{} is already being sourced", path.display()));
+            return Ok(true);
+        }
+        let script = match std::fs::read_to_string(path) {
+            Ok(script) => script,
+            Err(e) => {
+                self.fail(format!("{}: {}", path.display(), e));
+                return Ok(true);
+            }
+        };
+
+        self.sourcing.push(canonical);
+        let mut keep_going = true;
+        for (idx, line) in script.lines().enumerate() {
+            let trimmed = line.trim();
+            if !self.in_multi_line && (trimmed.is_empty() || trimmed.starts_with('#')) {
+                continue;
+            }
+            let location = format!("{}:{}", path.display(), idx + 1);
+            match self.run_script_line(line, Some(&location)) {
+                Ok(true) => {}
+                Ok(false) => {
+                    keep_going = false;
+                    break;
+                }
+                Err(e) => println!("[-] {}: {}", location, e),
+            }
+        }
+        self.sourcing.pop();
+        self.failed = false;
+        Ok(keep_going)
+    }
+
+    pub fn restore_session(&mut self, dir: &Path) -> io::Result<()> {
+        let session = match Session::load(dir) {
+            Ok(Some(session)) if !session.is_empty() => session,
+            Ok(_) => return Session::clear(dir),
+            Err(e) => {
+                println!("[-] could not read last session: {}", e);
+                return Session::clear(dir);
+            }
+        };
+
+        println!("[?] last session: {}", session.describe());
+        if self.confirm("restore it? [y/N] ")? {
+            let description = session.describe();
+            session.restore(self);
+            println!("[+] restored {}", description);
+            if self.edit_mode {
+                println!("    still in edit mode, :save or :cancel when done");
+            }
+            if self.in_multi_line {
+                println!("    still in multi-line input, :ml to finish");
+            }
+        } else {
+            println!("[-] last session discarded");
+        }
+        Session::clear(dir)
+    }
+
+    fn define_alias(&mut self, name: &str, expansion: &str, is_macro: bool) {
+        let result = if is_macro {
+            self.aliases.define_macro(name, expansion)
+        } else {
+            self.aliases.define_alias(name, expansion)
+        };
+        match result {
+            Err(e) => self.fail(e),
+            Ok(()) => match self.aliases.describe(name) {
+                Some(expansion) => println!("[+] :{} = {}", name, expansion),
+                None => println!("[+] removed :{}", name),
+            },
+        }
+    }
+
+    fn list_aliases(&self) {
+        if self.aliases.is_empty() {
+            println!("[-] no aliases defined");
+            println!("    use :alias name = command or :macro name = cmd; cmd");
+            return;
+        }
+        println!("\nAliases:");
+        for name in self.aliases.names() {
+            let expansion = self.aliases.describe(&name).unwrap_or_default();
+            println!("  {:<19} ► {}", format!(":{}", name), expansion);
+        }
+    }
+
     pub fn execute_command(&mut self, command: Command) -> io::Result<bool> {
         match command {
             Command::Write(text) => {
@@ -225,7 +533,7 @@ impl Editor {
                 if self.edit_mode {
                     self.edit_line(num)?;
                 } else {
-                    println!("[-] not in edit mode. use :edit");
+                    self.fail("not in edit mode. use :edit");
                 }
                 Ok(true)
             }
@@ -261,27 +569,81 @@ impl Editor {
                 }
                 Ok(true)
             }
-            Command::Search(term) => {
-                let mut found = false;
-                let mut results = Vec::new();
-
-                for (i, line) in self.content.lines().enumerate() {
-                    if line.contains(&term) {
-                        found = true;
-                        results.push((i + 1, line));
+            Command::Search(query) => {
+                self.run_search(query)?;
+                Ok(true)
+            }
+            Command::Retry(num) => {
+                match (self.suggestions.get(num.wrapping_sub(1)), &self.last_query) {
+                    (Some(suggestion), Some(query)) => {
+                        let query = SearchQuery {
+                            term: suggestion.clone(),
+                            ignore_case: true,
+                            ..query.clone()
+                        };
+                        self.run_search(query)?;
                     }
+                    _ => self.fail(format!("no suggestion {}", num)),
                 }
-
-                if found {
-                    println!("\nsearch results for '{}':", term);
-                    println!("{}", "=".repeat(40));
-                    for (line_num, content) in &results {
-                        println!("{:>4}: {}", line_num, content);
+                Ok(true)
+            }
+            Command::Open(num) => {
+                match self.last_listing.get(num.wrapping_sub(1)).cloned() {
+                    Some(name) => {
+                        if self.modified {
+                            self.fail("current note has unsaved changes.");
+                            println!("    save first with :save or force load with :n! then :open");
+                        } else {
+                            self.load_file(&name)?;
+                        }
                     }
-                    println!("{}", "=".repeat(40));
-                    println!("found {} matching line(s)\n", results.len());
+                    None => self.fail(format!("no result {}. run :grep or :ls first", num)),
+                }
+                Ok(true)
+            }
+            Command::Find(query) => {
+                self.find(&query);
+                Ok(true)
+            }
+            Command::Replace(query, replacement) => {
+                self.replace(&query, &replacement)?;
+                Ok(true)
+            }
+            Command::Quick(options) => {
+                self.quick_capture(&options)?;
+                Ok(true)
+            }
+            Command::AppendStdin(fence) => {
+                let text = QuickCapture::read_stdin()?;
+                if text.trim().is_empty() {
+                    self.fail("nothing read from stdin");
                 } else {
-                    println!("[-] no matches found for '{}'\n", term);
+                    if !self.content.is_empty() && !self.content.ends_with('\n') {
+                        self.content.push('\n');
+                    }
+                    self.content.push_str(&QuickCapture::stdin_block(&text, fence.as_deref()));
+                    self.modified = true;
+                    self.invalidate_stats_cache();
+                    println!("[+] appended {} line(s) from stdin", text.lines().count());
+                }
+                Ok(true)
+            }
+            Command::Vault(name) => {
+                match name {
+                    Some(name) => self.switch_vault(&name)?,
+                    None => self.list_vaults(),
+                }
+                Ok(true)
+            }
+            Command::Export(options) => {
+                if self.modified {
+                    println!("[-] current note has unsaved changes, exporting the saved version");
+                }
+                match Exporter::export(&self.notes_dir, &options) {
+                    Ok((path, count)) => {
+                        println!("[+] exported {} note(s) ({}) to {}", count, options.describe(), path.display());
+                    }
+                    Err(e) => self.fail(format!("export failed: {}", e)),
                 }
                 Ok(true)
             }
@@ -305,12 +667,14 @@ impl Editor {
             }
             Command::Save(name_opt) => {
                 if let Some(name) = name_opt {
-                    let file_path = FileOperations::save_file(
+                    let meta = self.note_meta();
+                    let file_path = FileOperations::save_note(
                         &self.notes_dir,
                         &FileOperations::format_content(&self.content),
-                        &self.current_tags,
+                        &meta,
                         Some(&name),
                     )?;
+                    self.current_meta = meta;
                     self.current_file = Some(file_path);
                     self.modified = false;
                     self.invalidate_stats_cache();
@@ -324,20 +688,27 @@ impl Editor {
             }
             Command::Load(name) => {
                 if self.modified {
-                    println!("[-] current note has unsaved changes.");
+                    self.fail("current note has unsaved changes.");
                     println!("    save first with :save or force load with :n! then :load");
                 } else {
+                    let name = self.resolve_note(&name)?;
                     self.load_file(&name)?;
                 }
                 Ok(true)
             }
+            Command::Preview(name) => {
+                let name = self.resolve_note(&name)?;
+                self.preview_note(&name)?;
+                Ok(true)
+            }
             Command::NewNote(force) => {
                 if self.modified && !force {
-                    println!("[-] note has unsaved changes");
+                    self.fail("note has unsaved changes");
                     println!("    use :n! to start new without saving, or :save first");
                 } else {
                     self.content.clear();
                     self.current_tags.clear();
+                    self.current_meta = Frontmatter::default();
                     self.current_file = None;
                     self.modified = false;
                     self.invalidate_stats_cache();
@@ -345,26 +716,64 @@ impl Editor {
                 }
                 Ok(true)
             }
+            Command::NewFromTemplate(name, values) => {
+                if self.modified {
+                    self.fail("note has unsaved changes");
+                    println!("    use :save first, or :n! to discard them");
+                } else {
+                    self.new_from_template(&name, values)?;
+                }
+                Ok(true)
+            }
+            Command::ListTemplates => {
+                self.list_templates()?;
+                Ok(true)
+            }
+            Command::SaveTemplate(name) => {
+                if self.content.is_empty() {
+                    self.fail("note is empty");
+                } else {
+                    let path = TemplateManager::save(&self.notes_dir, &name, &self.content, &self.note_meta())?;
+                    println!("[+] saved template {}", path.file_name().unwrap().to_string_lossy());
+                }
+                Ok(true)
+            }
             Command::Tag(tag) => {
                 self.add_tag(tag);
                 Ok(true)
             }
-            Command::ListTags => {
-                self.list_tags()?;
+            Command::Untag(tag) => {
+                self.remove_tag(&tag);
+                Ok(true)
+            }
+            Command::TagRename(old, new) => {
+                self.retag(&old, &new, false)?;
+                Ok(true)
+            }
+            Command::TagMerge(from, into) => {
+                self.retag(&from, &into, true)?;
+                Ok(true)
+            }
+            Command::ListTags(tree) => {
+                self.list_tags(tree)?;
                 Ok(true)
             }
             Command::ListByTag(tag) => {
                 self.list_by_tag(&tag)?;
                 Ok(true)
             }
-            Command::ListFiles => {
-                self.list_saved_notes()?;
-                println!("type ':load [name]' to load a note");
+            Command::Meta(action) => {
+                self.run_meta(action);
+                Ok(true)
+            }
+            Command::ListFiles(filters) => {
+                self.list_saved_notes(&filters)?;
+                println!("type ':load [N|name]' to load a note, ':preview [N|name]' to view it");
                 println!("type ':save [name]' to save current note with a specific name");
                 Ok(true)
             }
-            Command::Help => {
-                self.show_help();
+            Command::Help(topic) => {
+                self.show_help(topic.as_deref());
                 Ok(true)
             }
             Command::Quit => {
@@ -375,42 +784,147 @@ impl Editor {
                 Ok(false)
             }
             Command::Invalid(cmd) => {
-                println!("[-] invalid command: {}", cmd);
+                self.fail(format!("invalid command: {}", cmd));
                 println!("    Use :help to see available commands");
                 Ok(true)
             }
+            Command::Source(path) => self.source_file(&Config::expand_path(&path)),
+            Command::Alias(None) => {
+                self.list_aliases();
+                Ok(true)
+            }
+            Command::Alias(Some((name, expansion))) => {
+                self.define_alias(&name, &expansion, false);
+                Ok(true)
+            }
+            Command::Macro(name, steps) => {
+                self.define_alias(&name, &steps, true);
+                Ok(true)
+            }
+            Command::Usage(name, e) => {
+                self.fail(e);
+                println!("    Use :help {} for details", name);
+                Ok(true)
+            }
+        }
+    }
+
+    fn help_line(spec: &CommandSpec) -> String {
+        let (indent, width) = if spec.parent.is_some() { (4, 17) } else { (2, 19) };
+        let aliases: Vec<String> = spec.aliases.iter().map(|alias| format!(":{}", alias)).collect();
+        let help = if aliases.is_empty() {
+            spec.help.to_string()
+        } else {
+            format!("{} (alias {})", spec.help, aliases.join(", "))
+        };
+        format!("{:indent$}{:<width$} ► {}", "", spec.usage(), help, indent = indent, width = width)
+    }
+
+    fn show_help(&mut self, topic: Option<&str>) {
+        let Some(topic) = topic else {
+            println!("\nCommands:");
+            for spec in COMMANDS {
+                println!("{}", Self::help_line(spec));
+                let indent = if spec.parent.is_some() { 6 } else { 4 };
+                for (option, help) in spec.options {
+                    println!("{:indent$}{:<width$} ► {}", "", option, help, indent = indent, width = 21 - indent);
+                }
+            }
+            if !self.aliases.is_empty() {
+                self.list_aliases();
+            }
+            println!("  type :help [command] for details on one command");
+            println!("                                  ↳ written by r00tkid");
+            return;
+        };
+
+        let Some(spec) = CommandParser::find(topic) else {
+            match self.aliases.describe(topic) {
+                Some(expansion) => println!(":{} = {}", topic, expansion),
+                None => self.fail(format!("unknown command: {}", topic)),
+            }
+            return;
+        };
+        println!("\n{}", spec.usage());
+        println!("{}", "=".repeat(40));
+        println!("  {}", spec.help);
+        if !spec.aliases.is_empty() {
+            let aliases: Vec<String> = spec.aliases.iter().map(|alias| format!(":{}", alias)).collect();
+            println!("  aliases: {}", aliases.join(", "));
+        }
+        if !spec.options.is_empty() {
+            println!("\n  options:");
+            for (option, help) in spec.options {
+                println!("    {:<19} ► {}", option, help);
+            }
+        }
+        let related: Vec<&CommandSpec> = COMMANDS
+            .iter()
+            .filter(|other| other.parent == Some(spec.name) || Some(other.name) == spec.parent)
+            .collect();
+        if !related.is_empty() {
+            println!("\n  see also:");
+            for other in related {
+                println!("    {:<19} ► {}", other.usage(), other.help);
+            }
+        }
+        println!("{}", "=".repeat(40));
+    }
+
+    pub fn resolve_note(&self, name: &str) -> io::Result<String> {
+        if let Ok(num) = name.parse::<usize>() {
+            let listing = if self.last_listing.is_empty() {
+                FileOperations::list_saved_notes(&self.notes_dir)?
+                    .into_iter()
+                    .map(|(filename, _, _)| filename)
+                    .collect()
+            } else {
+                self.last_listing.clone()
+            };
+            if let Some(filename) = listing.get(num.wrapping_sub(1)) {
+                return Ok(filename.clone());
+            }
         }
+        Ok(name.to_string())
     }
 
-    fn show_help(&self) {
-        println!("\nCommands:");
-        println!("  :quit               ► exit rustynotes");
-        println!("  :n  / :n!           ► new note (with/without warning)");
-        println!("  :save [name]        ► save note (with optional name)");
-        println!("  :load [name]        ► load note");
-        println!("  :ls                 ► list saved notes");
-        println!("  :list               ► show current note");
-        println!("  :stats              ► show note statistics");
-        println!("  :tag [name]         ► add tag to current note");
-        println!("    :tags             ► list all tags");
-        println!("    :tagged [tag]     ► list notes with specific tag");
-        println!("  :search [keyword]   ► search for keyword");
-        println!("  :ml                 ► start/end multi-line input");
-        println!("  :edit               ► start edit mode");
-        println!("    :line N           ► select line to edit");
-        println!("    :save             ► save changes");
-        println!("    :cancel           ► discard changes");
-        println!("                                  ↳ written by r00tkid");
+    fn preview_note(&mut self, name: &str) -> io::Result<()> {
+        let (content, tags, path) = match FileOperations::load_file(&self.notes_dir, name) {
+            Ok(note) => note,
+            Err(e) => {
+                self.fail(e);
+                return Ok(());
+            }
+        };
+        let modified = std::fs::metadata(&path)?.modified()?;
+
+        println!("\n--- {} ---", path.file_name().unwrap().to_string_lossy());
+        println!("modified: {}", DateTime::<Local>::from(modified).format("%Y-%m-%d %H:%M"));
+        if !tags.is_empty() {
+            println!("tags: {}", tags.join(", "));
+        }
+        println!("{}", "=".repeat(40));
+        println!("{}", content.trim_end());
+        println!("{}", "=".repeat(40));
+        Ok(())
     }
 
-    fn list_saved_notes(&self) -> io::Result<()> {
+    fn list_saved_notes(&mut self, filters: &[MetaFilter]) -> io::Result<()> {
         println!("\nsaved notes:");
         println!("{}", "=".repeat(40));
 
-        let notes = FileOperations::list_saved_notes(&self.notes_dir)?;
+        let mut notes = FileOperations::list_saved_notes(&self.notes_dir)?;
+        if !filters.is_empty() {
+            let matching = FileOperations::find_notes_by_meta(&self.notes_dir, filters)?;
+            notes.retain(|(filename, _, _)| matching.contains(filename));
+        }
 
         if notes.is_empty() {
-            println!("[-] no saved notes found.");
+            if filters.is_empty() {
+                println!("[-] no saved notes found.");
+            } else {
+                println!("[-] no notes match the --where filter.");
+            }
             println!("{}", "=".repeat(40));
             return Ok(());
         }
@@ -437,11 +951,216 @@ impl Editor {
             }
         }
         println!("{}", "=".repeat(40));
+
+        self.last_listing = notes.into_iter().map(|(filename, _, _)| filename).collect();
+        Ok(())
+    }
+
+    pub fn fill_template(
+        &self,
+        template: &Template,
+        note_name: Option<&str>,
+        given: Vec<(String, String)>,
+    ) -> io::Result<Option<String>> {
+        let mut values = TemplateManager::builtin_values(note_name);
+        values.extend(given);
+
+        let mut line_editor: Option<LineEditor<(), _>> = None;
+        for variable in TemplateManager::variables(template) {
+            if values.contains_key(&variable) {
+                continue;
+            }
+            let prompt_editor = match &mut line_editor {
+                Some(editor) => editor,
+                None => line_editor.insert(LineEditor::new().map_err(io::Error::other)?),
+            };
+            match prompt_editor.readline(&format!("{}: ", variable)) {
+                Ok(value) => {
+                    values.insert(variable, value.trim().to_string());
+                }
+                Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => return Ok(None),
+                Err(err) => return Err(io::Error::other(err)),
+            }
+        }
+
+        Ok(Some(TemplateManager::render(template, &values)))
+    }
+
+    pub fn new_from_template(&mut self, name: &str, mut values: Vec<(String, String)>) -> io::Result<bool> {
+        let note_name = values
+            .iter()
+            .position(|(key, _)| key == "name")
+            .map(|idx| values.remove(idx).1);
+
+        match TemplateManager::load(&self.notes_dir, name) {
+            Ok(template) => {
+                let content = match self.fill_template(&template, note_name.as_deref(), values)? {
+                    Some(content) => content,
+                    None => {
+                        println!("\n[-] template cancelled");
+                        return Ok(false);
+                    }
+                };
+                self.content = content;
+                self.current_tags = template.meta.tags();
+                self.current_meta = template.meta;
+                self.current_file = note_name
+                    .as_ref()
+                    .map(|name| self.notes_dir.join(format!("{}.md", name.trim_end_matches(".md"))));
+                self.modified = true;
+                self.invalidate_stats_cache();
+                let source = match template.source {
+                    TemplateSource::User => "user",
+                    TemplateSource::Builtin => "default",
+                };
+                println!("[+] started new note from {} template: {}", source, template.name);
+                if !self.current_tags.is_empty() {
+                    println!("    tags: {}", self.current_tags.join(", "));
+                }
+                Ok(true)
+            }
+            Err(e) => {
+                self.fail(e);
+                println!("    use :templates to see available templates");
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn quick_capture(&mut self, options: &QuickOptions) -> io::Result<bool> {
+        let text = options.entry()?;
+        if text.trim().is_empty() {
+            self.fail("nothing to capture");
+            return Ok(false);
+        }
+
+        let result = if let Some(existing) = &options.add {
+            QuickCapture::append(&self.notes_dir, existing, &text, &options.tags)
+        } else {
+            let name = options.note_name();
+            let (body, mut meta) = match &options.template {
+                Some(template_name) => {
+                    let template = match TemplateManager::load(&self.notes_dir, template_name) {
+                        Ok(template) => template,
+                        Err(e) => {
+                            self.fail(e);
+                            return Ok(false);
+                        }
+                    };
+                    match self.fill_template(&template, name.as_deref(), Vec::new())? {
+                        Some(body) => (body, template.meta),
+                        None => {
+                            println!("\n[-] quick capture cancelled");
+                            return Ok(false);
+                        }
+                    }
+                }
+                None => (String::new(), Frontmatter::default()),
+            };
+            meta.set_tags(&QuickCapture::merged_tags(&meta.tags(), &options.tags));
+            QuickCapture::create(&self.notes_dir, name.as_deref(), &body, &text, &meta)
+        };
+
+        let path = match result {
+            Ok(path) => path,
+            Err(e) => {
+                self.fail(e);
+                return Ok(false);
+            }
+        };
+
+        let filename = path.file_name().unwrap().to_string_lossy();
+        match (&options.add, &options.template) {
+            (Some(_), _) => println!("[+] appended to {}", filename),
+            (None, Some(template)) => println!("[+] created from {} template and saved to {}", template, filename),
+            (None, None) => println!("[+] saved to {}", filename),
+        }
+        if !options.tags.is_empty() {
+            println!("    tags: {}", options.tags.join(", "));
+        }
+        if self.current_file.as_ref() == Some(&path) {
+            println!("    {} is open, :load it again to see the change", filename);
+        }
+        Ok(true)
+    }
+
+    fn list_vaults(&self) {
+        println!("\nvaults:");
+        println!("{}", "=".repeat(40));
+        let current = self.current_vault.as_deref();
+        let marker = |active: bool| if active { "*" } else { " " };
+        if !self.vaults.contains_key("default") {
+            let active = current.is_none() && self.notes_dir == self.default_dir;
+            println!("{} default  ({})", marker(active), self.default_dir.display());
+        }
+        for (name, dir) in &self.vaults {
+            println!("{} {}  ({})", marker(current == Some(name.as_str())), name, dir.display());
+        }
+        if current.is_none() && self.notes_dir != self.default_dir {
+            println!("* [custom]  ({})", self.notes_dir.display());
+        }
+        println!("{}", "=".repeat(40));
+        println!("type ':vault [name]' to switch, add vaults under [vaults] in the config file");
+    }
+
+    fn switch_vault(&mut self, name: &str) -> io::Result<()> {
+        if self.modified {
+            self.fail("note has unsaved changes");
+            println!("    use :save first, or :n! to discard them");
+            return Ok(());
+        }
+
+        let dir = match self.vaults.get(name) {
+            Some(dir) => dir.clone(),
+            None if name == "default" => self.default_dir.clone(),
+            None => {
+                self.fail(format!("unknown vault: {}", name));
+                println!("    use :vault to list vaults");
+                return Ok(());
+            }
+        };
+
+        std::fs::create_dir_all(&dir)?;
+        self.notes_dir = dir;
+        self.current_vault = Some(name.to_string()).filter(|name| self.vaults.contains_key(name));
+        self.content.clear();
+        self.current_tags.clear();
+        self.current_meta = Frontmatter::default();
+        self.current_file = None;
+        self.edit_mode = false;
+        self.edit_buffer.clear();
+        self.last_listing.clear();
+        self.suggestions.clear();
+        self.invalidate_stats_cache();
+        println!("[+] switched to vault {} ({})", name, self.notes_dir.display());
         Ok(())
     }
 
-    fn list_tags(&self) -> io::Result<()> {
-        let (all_tags, tag_counts) = FileOperations::get_all_tags(&self.notes_dir, &self.current_tags)?;
+    fn list_templates(&self) -> io::Result<()> {
+        let user = TemplateManager::user_names(&self.notes_dir)?;
+        let builtin: Vec<String> = TemplateManager::builtin_names()
+            .into_iter()
+            .filter(|name| !user.contains(name))
+            .collect();
+
+        println!("\ntemplates:");
+        println!("{}", "=".repeat(40));
+        for (label, names) in [("user templates:", &user), ("default templates:", &builtin)] {
+            if names.is_empty() {
+                continue;
+            }
+            println!("{}", label);
+            for name in names {
+                println!("  * {}", name);
+            }
+        }
+        println!("{}", "=".repeat(40));
+        println!("type ':new [template] [--name note] [--key value]' to start a note from a template");
+        Ok(())
+    }
+
+    fn list_tags(&self, tree: bool) -> io::Result<()> {
+        let (all_tags, tag_counts) = FileOperations::get_all_tags(&self.notes_dir, self.current_file.as_deref(), &self.current_tags)?;
 
         if all_tags.is_empty() {
             println!("[-] no tags found");
@@ -449,15 +1168,256 @@ impl Editor {
             println!("\navailable tags:");
             println!("{}", "=".repeat(40));
             let mut tags: Vec<_> = all_tags.iter().collect();
-            tags.sort();
+            tags.sort_by(|a, b| a.split('/').cmp(b.split('/')));
             for tag in tags {
-                println!("  {} ({} notes)", tag, tag_counts.get(tag).unwrap_or(&0));
+                let count = tag_counts.get(tag).unwrap_or(&0);
+                if tree {
+                    let depth = tag.matches('/').count();
+                    let leaf = tag.rsplit('/').next().unwrap_or(tag);
+                    println!("  {}{} ({})", "  ".repeat(depth), leaf, count);
+                } else {
+                    println!("  {} ({} notes)", tag, count);
+                }
             }
             println!("{}", "=".repeat(40));
         }
         Ok(())
     }
 
+    fn target_lines(&self) -> Vec<String> {
+        if self.edit_mode {
+            self.edit_buffer.clone()
+        } else {
+            self.content.lines().map(String::from).collect()
+        }
+    }
+
+    fn find(&mut self, query: &SearchQuery) {
+        let matcher = match query.compile() {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                self.fail(e);
+                return;
+            }
+        };
+
+        let lines = self.target_lines();
+        let hits: Vec<usize> = (0..lines.len()).filter(|&i| matcher.is_match(&lines[i])).collect();
+
+        if hits.is_empty() {
+            println!("[-] '{}' not found\n", query.term);
+            return;
+        }
+
+        println!("\noccurrences of '{}':", query.term);
+        println!("{}", "=".repeat(40));
+        let mut total = 0;
+        for &i in &hits {
+            total += matcher.pattern.find_iter(&lines[i]).count();
+            println!("{:>4}: {}", i + 1, matcher.highlight(&lines[i]));
+        }
+        println!("{}", "=".repeat(40));
+        println!("found {} occurrence(s) on {} line(s)\n", total, hits.len());
+    }
+
+    fn replace(&mut self, query: &SearchQuery, replacement: &str) -> io::Result<()> {
+        let matcher = match query.compile() {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                self.fail(e);
+                return Ok(());
+            }
+        };
+        if query.regex {
+            if let Err(e) = matcher.check_replacement(replacement) {
+                self.fail(e);
+                return Ok(());
+            }
+        }
+
+        let mut lines = self.target_lines();
+        let mut changes = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            if matcher.is_match(line) {
+                let new_line = matcher.replace(line, replacement, query.regex);
+                if new_line != *line {
+                    changes.push((i, new_line));
+                }
+            }
+        }
+
+        if changes.is_empty() {
+            println!("[-] '{}' not found\n", query.term);
+            return Ok(());
+        }
+
+        println!("\nreplace '{}' with '{}':", query.term, replacement);
+        println!("{}", "=".repeat(40));
+        for (i, new_line) in &changes {
+            println!("{:>4} - {}", i + 1, lines[*i]);
+            println!("{:>4} + {}", i + 1, new_line);
+        }
+        println!("{}", "=".repeat(40));
+
+        if !self.confirm(&format!("apply {} change(s)? [y/N] ", changes.len()))? {
+            println!("[-] replace cancelled");
+            return Ok(());
+        }
+
+        let count = changes.len();
+        for (i, new_line) in changes {
+            lines[i] = new_line;
+        }
+
+        if self.edit_mode {
+            self.edit_buffer = lines;
+            println!("[+] replaced {} line(s) in edit buffer", count);
+            println!("    use :save to keep the changes");
+        } else {
+            let trailing_newline = self.content.ends_with('\n');
+            self.content = lines.join("\n");
+            if trailing_newline {
+                self.content.push('\n');
+            }
+            self.modified = true;
+            self.invalidate_stats_cache();
+            println!("[+] replaced {} line(s)", count);
+        }
+        Ok(())
+    }
+
+    fn confirm(&self, prompt: &str) -> io::Result<bool> {
+        let mut line_editor = LineEditor::<(), _>::new().map_err(io::Error::other)?;
+        match line_editor.readline(prompt) {
+            Ok(answer) => Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")),
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => Ok(false),
+            Err(err) => Err(io::Error::other(err)),
+        }
+    }
+
+    pub fn run_search(&mut self, query: SearchQuery) -> io::Result<bool> {
+        if let Some(extractor) = &query.extract {
+            return self.run_extract(extractor, query.all_notes);
+        }
+
+        let matcher = match query.compile() {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                self.fail(e);
+                return Ok(false);
+            }
+        };
+        let found = if query.all_notes {
+            self.search_all_notes(&query, &matcher)?
+        } else {
+            self.search_current_note(&query, &matcher)
+        };
+
+        self.suggestions.clear();
+        if !found && !query.regex {
+            self.suggest_terms(&query.term)?;
+        }
+        self.last_query = Some(query);
+        Ok(found)
+    }
+
+    fn run_extract(&self, extractor: &Extractor, all_notes: bool) -> io::Result<bool> {
+        let results = if all_notes {
+            ContentExtractor::extract_notes(&self.notes_dir, extractor)?
+        } else {
+            let filename = self
+                .current_file
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "[not saved]".to_string());
+            ContentExtractor::extract(extractor, &self.content)
+                .into_iter()
+                .map(|(line, text)| Extracted {
+                    filename: filename.clone(),
+                    line,
+                    text,
+                })
+                .collect()
+        };
+
+        let scope = if all_notes { "saved notes" } else { "current note" };
+        if results.is_empty() {
+            println!("[-] no {} found in {}\n", extractor.describe(), scope);
+            return Ok(false);
+        }
+
+        println!("\n{} in {}:", extractor.describe(), scope);
+        println!("{}", "=".repeat(40));
+        for hit in &results {
+            let mut lines = hit.text.lines();
+            println!("  {}:{}  {}", hit.filename, hit.line, lines.next().unwrap_or(""));
+            for line in lines {
+                println!("      | {}", line);
+            }
+        }
+        println!("{}", "=".repeat(40));
+        println!("found {} item(s)\n", results.len());
+        Ok(true)
+    }
+
+    fn suggest_terms(&mut self, term: &str) -> io::Result<()> {
+        let vocabulary = Searcher::build_vocabulary(&self.notes_dir, &self.content)?;
+        self.suggestions = Searcher::suggest(term, &vocabulary, 3);
+
+        if let Some(best) = self.suggestions.first() {
+            println!("did you mean \"{}\"?", best);
+            if self.suggestions.len() > 1 {
+                for (idx, suggestion) in self.suggestions.iter().enumerate() {
+                    println!("  {}. {}", idx + 1, suggestion);
+                }
+            }
+            println!("type ':retry [N]' to search again with a suggestion\n");
+        }
+        Ok(())
+    }
+
+    fn search_current_note(&self, query: &SearchQuery, matcher: &Matcher) -> bool {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let hits = Searcher::matching_lines(&self.content, matcher);
+
+        if hits.is_empty() {
+            println!("[-] no matches found for '{}'", query.term);
+            return false;
+        }
+
+        println!("\nsearch results for '{}':", query.term);
+        println!("{}", "=".repeat(40));
+        Searcher::display_lines(&lines, &hits, matcher, query.context(0), "");
+        println!("{}", "=".repeat(40));
+        println!("found {} matching line(s)\n", hits.len());
+        true
+    }
+
+    fn search_all_notes(&mut self, query: &SearchQuery, matcher: &Matcher) -> io::Result<bool> {
+        let results = Searcher::search_notes(&self.notes_dir, matcher)?;
+        let term = &query.term;
+
+        if results.is_empty() {
+            println!("[-] no matches found for '{}' in saved notes", term);
+            return Ok(false);
+        }
+
+        println!("\nsearch results for '{}' in saved notes:", term);
+        println!("{}", "=".repeat(40));
+        for (idx, matches) in results.iter().enumerate() {
+            Searcher::display_note_matches(idx + 1, matches, matcher, query.context(1));
+        }
+        println!("{}", "=".repeat(40));
+
+        let total: usize = results.iter().map(|m| m.hits.len()).sum();
+        println!("found {} matching line(s) in {} note(s)", total, results.len());
+        println!("type ':open N' to load a note from the results\n");
+
+        self.last_listing = results.into_iter().map(|m| m.filename).collect();
+        Ok(true)
+    }
+
     fn list_by_tag(&self, tag: &str) -> io::Result<()> {
         let found_notes = FileOperations::find_notes_by_tag(&self.notes_dir, tag)?;
 
@@ -0,0 +1,332 @@
+use crate::file_ops::FileOperations;
+use crate::pdf::PdfRenderer;
+use crate::search::SearchQuery;
+use chrono::{DateTime, Local, NaiveDate};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Result<ExportFormat, String> {
+        match name.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "json" => Ok(ExportFormat::Json),
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(format!("unknown export format: {} (md, html, json, pdf)", name)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub tag: Option<String>,
+    pub search: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub format: ExportFormat,
+    pub output: Option<PathBuf>,
+}
+
+impl ExportOptions {
+    pub fn parse(args: &[String]) -> Result<ExportOptions, String> {
+        let mut options = ExportOptions {
+            tag: None,
+            search: None,
+            from: None,
+            to: None,
+            format: ExportFormat::Markdown,
+            output: None,
+        };
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let mut value = || {
+                iter.next()
+                    .cloned()
+                    .ok_or_else(|| format!("{} requires a value", arg))
+            };
+            match arg.as_str() {
+                "--tag" => options.tag = Some(value()?.to_lowercase()),
+                "--search" => options.search = Some(value()?),
+                "--from" => options.from = Some(Self::parse_date(&value()?)?),
+                "--to" => options.to = Some(Self::parse_date(&value()?)?),
+                "--format" => options.format = ExportFormat::parse(&value()?)?,
+                "--out" | "-o" => options.output = Some(PathBuf::from(value()?)),
+                _ => return Err(format!("unknown export option: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn parse_date(value: &str) -> Result<NaiveDate, String> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", value))
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tag) = &self.tag {
+            parts.push(format!("tag '{}'", tag));
+        }
+        if let Some(term) = &self.search {
+            parts.push(format!("search '{}'", term));
+        }
+        if let Some(from) = self.from {
+            parts.push(format!("from {}", from));
+        }
+        if let Some(to) = self.to {
+            parts.push(format!("to {}", to));
+        }
+        if parts.is_empty() {
+            "all notes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+pub struct ExportNote {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub modified: DateTime<Local>,
+    pub content: String,
+}
+
+pub struct Exporter;
+
+impl Exporter {
+    pub fn select(notes_dir: &Path, options: &ExportOptions) -> io::Result<Vec<ExportNote>> {
+        let tagged = match &options.tag {
+            Some(tag) => Some(FileOperations::find_notes_by_tag(notes_dir, tag)?),
+            None => None,
+        };
+        let matcher = match &options.search {
+            Some(term) => {
+                let query = SearchQuery {
+                    term: term.clone(),
+                    ignore_case: true,
+                    ..SearchQuery::default()
+                };
+                Some(query.compile().map_err(io::Error::other)?)
+            }
+            None => None,
+        };
+
+        let mut notes = Vec::new();
+        for (name, modified, tags) in FileOperations::list_saved_notes(notes_dir)? {
+            if tagged.as_ref().is_some_and(|tagged| !tagged.contains(&name)) {
+                continue;
+            }
+            let date = modified.date_naive();
+            if options.from.is_some_and(|from| date < from) || options.to.is_some_and(|to| date > to) {
+                continue;
+            }
+
+            let (content, _, _) = FileOperations::load_file(notes_dir, &name)?;
+            if let Some(matcher) = &matcher {
+                if !content.lines().any(|line| matcher.is_match(line)) {
+                    continue;
+                }
+            }
+
+            notes.push(ExportNote {
+                name,
+                tags,
+                modified,
+                content,
+            });
+        }
+
+        notes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(notes)
+    }
+
+    pub fn export(notes_dir: &Path, options: &ExportOptions) -> io::Result<(PathBuf, usize)> {
+        let notes = Self::select(notes_dir, options)?;
+        if notes.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no notes match {}", options.describe()),
+            ));
+        }
+
+        let rendered = match options.format {
+            ExportFormat::Markdown => Self::render_markdown(&notes).into_bytes(),
+            ExportFormat::Html => Self::render_html(&notes).into_bytes(),
+            ExportFormat::Json => Self::render_json(&notes).into_bytes(),
+            ExportFormat::Pdf => PdfRenderer::render(&notes),
+        };
+
+        let path = match &options.output {
+            Some(path) => path.clone(),
+            None => {
+                let dir = notes_dir.join("exports");
+                fs::create_dir_all(&dir)?;
+                let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+                dir.join(format!("export_{}.{}", timestamp, options.format.extension()))
+            }
+        };
+
+        fs::write(&path, rendered)?;
+        Ok((path, notes.len()))
+    }
+
+    fn anchor(name: &str) -> String {
+        name.trim_end_matches(".md")
+            .to_lowercase()
+            .chars()
+            .filter_map(|c| match c {
+                'a'..='z' | '0'..='9' | '-' | '_' => Some(c),
+                ' ' | '.' => Some('-'),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn render_markdown(notes: &[ExportNote]) -> String {
+        let mut out = String::new();
+        out.push_str("# rustynotes export\n\n");
+        out.push_str(&format!(
+            "exported {} - {} note(s)\n\n",
+            Local::now().format("%Y-%m-%d %H:%M"),
+            notes.len()
+        ));
+
+        out.push_str("## Contents\n\n");
+        for (idx, note) in notes.iter().enumerate() {
+            out.push_str(&format!("{}. [{}](#{})\n", idx + 1, note.name, Self::anchor(&note.name)));
+        }
+
+        for note in notes {
+            out.push_str("\n---\n\n");
+            out.push_str(&format!("## {}\n\n", note.name.trim_end_matches(".md")));
+            out.push_str(&format!("- modified: {}\n", note.modified.format("%Y-%m-%d %H:%M")));
+            if !note.tags.is_empty() {
+                out.push_str(&format!("- tags: {}\n", note.tags.join(", ")));
+            }
+            out.push('\n');
+            out.push_str(note.content.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn escape_html(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    pub fn render_html(notes: &[ExportNote]) -> String {
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        out.push_str("<title>rustynotes export</title>\n<style>\n");
+        out.push_str("body { font-family: sans-serif; max-width: 60em; margin: 2em auto; color: #222; }\n");
+        out.push_str("pre { background: #f5f5f5; padding: 1em; white-space: pre-wrap; }\n");
+        out.push_str(".meta { color: #666; font-size: 0.9em; }\n");
+        out.push_str(".tag { background: #e0e0e0; border-radius: 3px; padding: 0 0.4em; margin-right: 0.3em; }\n");
+        out.push_str("</style>\n</head>\n<body>\n");
+
+        out.push_str("<h1>rustynotes export</h1>\n");
+        out.push_str(&format!(
+            "<p class=\"meta\">exported {} - {} note(s)</p>\n",
+            Local::now().format("%Y-%m-%d %H:%M"),
+            notes.len()
+        ));
+
+        out.push_str("<h2>Contents</h2>\n<ol>\n");
+        for note in notes {
+            out.push_str(&format!(
+                "<li><a href=\"#{}\">{}</a></li>\n",
+                Self::anchor(&note.name),
+                Self::escape_html(&note.name)
+            ));
+        }
+        out.push_str("</ol>\n");
+
+        for note in notes {
+            out.push_str(&format!("<section id=\"{}\">\n<hr>\n", Self::anchor(&note.name)));
+            out.push_str(&format!("<h2>{}</h2>\n", Self::escape_html(note.name.trim_end_matches(".md"))));
+            out.push_str(&format!(
+                "<p class=\"meta\">modified {}",
+                note.modified.format("%Y-%m-%d %H:%M")
+            ));
+            for tag in &note.tags {
+                out.push_str(&format!(" <span class=\"tag\">{}</span>", Self::escape_html(tag)));
+            }
+            out.push_str("</p>\n");
+            out.push_str(&format!("<pre>{}</pre>\n</section>\n", Self::escape_html(note.content.trim_end())));
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn escape_json(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len() + 2);
+        escaped.push('"');
+        for c in text.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                c => escaped.push(c),
+            }
+        }
+        escaped.push('"');
+        escaped
+    }
+
+    pub fn render_json(notes: &[ExportNote]) -> String {
+        let entries: Vec<String> = notes
+            .iter()
+            .map(|note| {
+                let tags: Vec<String> = note.tags.iter().map(|tag| Self::escape_json(tag)).collect();
+                format!(
+                    "  {{\n    \"name\": {},\n    \"tags\": [{}],\n    \"modified\": {},\n    \"content\": {}\n  }}",
+                    Self::escape_json(&note.name),
+                    tags.join(", "),
+                    Self::escape_json(&note.modified.to_rfc3339()),
+                    Self::escape_json(&note.content)
+                )
+            })
+            .collect();
+
+        if entries.is_empty() {
+            "[]\n".to_string()
+        } else {
+            format!("[\n{}\n]\n", entries.join(",\n"))
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::file_ops::FileOperations;
+use regex::Regex;
+use std::io;
+use std::path::Path;
+
+const URL_PATTERN: &str = r#"https?://[^\s<>"'`)\]]+"#;
+const IP_PATTERN: &str = r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b";
+const EMAIL_PATTERN: &str = r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b";
+const HEADER_PATTERN: &str = r"^[A-Za-z0-9-]+:\s*\S";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Extractor {
+    Urls,
+    Code(Option<String>),
+    Headers,
+    Ips,
+    Emails,
+}
+
+impl Extractor {
+    pub fn describe(&self) -> String {
+        match self {
+            Extractor::Urls => "urls".to_string(),
+            Extractor::Code(Some(lang)) => format!("{} code blocks", lang),
+            Extractor::Code(None) => "code blocks".to_string(),
+            Extractor::Headers => "http headers".to_string(),
+            Extractor::Ips => "ip addresses".to_string(),
+            Extractor::Emails => "email addresses".to_string(),
+        }
+    }
+}
+
+pub struct Extracted {
+    pub filename: String,
+    pub line: usize,
+    pub text: String,
+}
+
+pub struct ContentExtractor;
+
+impl ContentExtractor {
+    pub fn extract(extractor: &Extractor, content: &str) -> Vec<(usize, String)> {
+        match extractor {
+            Extractor::Urls => Self::find_pattern(URL_PATTERN, content),
+            Extractor::Ips => Self::find_pattern(IP_PATTERN, content),
+            Extractor::Emails => Self::find_pattern(EMAIL_PATTERN, content),
+            Extractor::Headers => Self::find_http_blocks(content),
+            Extractor::Code(lang) => Self::find_code_blocks(content, lang.as_deref()),
+        }
+    }
+
+    pub fn extract_notes(notes_dir: &Path, extractor: &Extractor) -> io::Result<Vec<Extracted>> {
+        let mut results = Vec::new();
+
+        for (filename, _, _) in FileOperations::list_saved_notes(notes_dir)? {
+            let content = match FileOperations::load_file(notes_dir, &filename) {
+                Ok((content, _, _)) => content,
+                Err(_) => continue,
+            };
+
+            for (line, text) in Self::extract(extractor, &content) {
+                results.push(Extracted {
+                    filename: filename.clone(),
+                    line,
+                    text,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn find_pattern(pattern: &str, content: &str) -> Vec<(usize, String)> {
+        let re = Regex::new(pattern).expect("invalid built-in pattern");
+        let mut found = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            for m in re.find_iter(line) {
+                let text = m.as_str().trim_end_matches(['.', ',', ';', ':']);
+                found.push((i + 1, text.to_string()));
+            }
+        }
+        found
+    }
+
+    fn find_http_blocks(content: &str) -> Vec<(usize, String)> {
+        let header = Regex::new(HEADER_PATTERN).expect("invalid built-in pattern");
+        let mut found = Vec::new();
+        let mut in_block = false;
+
+        for (i, line) in content.lines().enumerate() {
+            let stripped = line.trim_start();
+            let stripped = stripped.strip_prefix("> ").unwrap_or(stripped);
+
+            if FileOperations::is_http_line(stripped) {
+                in_block = true;
+                found.push((i + 1, stripped.to_string()));
+            } else if in_block && header.is_match(stripped) {
+                found.push((i + 1, stripped.to_string()));
+            } else {
+                in_block = false;
+            }
+        }
+        found
+    }
+
+    fn find_code_blocks(content: &str, lang: Option<&str>) -> Vec<(usize, String)> {
+        let mut found = Vec::new();
+        let mut block: Option<(usize, String, Vec<&str>)> = None;
+
+        for (i, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            match block.take() {
+                None => {
+                    if let Some(tag) = trimmed.strip_prefix("```") {
+                        block = Some((i + 1, tag.trim().to_lowercase(), Vec::new()));
+                    }
+                }
+                Some((start, tag, lines)) if trimmed.starts_with("```") => {
+                    let wanted = lang.is_none_or(|lang| tag == lang.to_lowercase());
+                    if wanted {
+                        let label = if tag.is_empty() { "text" } else { tag.as_str() };
+                        found.push((start, format!("[{}]\n{}", label, lines.join("\n"))));
+                    }
+                }
+                Some((start, tag, mut lines)) => {
+                    lines.push(line);
+                    block = Some((start, tag, lines));
+                }
+            }
+        }
+        found
+    }
+}
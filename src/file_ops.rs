@@ -1,42 +1,47 @@
+use crate::frontmatter::{Frontmatter, MetaFilter};
 use chrono::{DateTime, Local};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+pub type SavedNote = (String, DateTime<Local>, Vec<String>);
 
 pub struct FileOperations;
 
 impl FileOperations {
-    pub fn load_file(
-        notes_dir: &PathBuf,
-        name: &str,
-    ) -> std::io::Result<(String, Vec<String>, PathBuf)> {
-        let path = if name.ends_with(".md") {
+    pub fn is_note(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "md")
+    }
+
+    pub fn note_paths(notes_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(notes_dir)? {
+            let path = entry?.path();
+            if Self::is_note(&path) {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    pub fn note_path(notes_dir: &Path, name: &str) -> PathBuf {
+        if name.ends_with(".md") {
             notes_dir.join(name)
         } else {
             notes_dir.join(format!("{}.md", name))
-        };
+        }
+    }
+
+    pub fn load_note(
+        notes_dir: &Path,
+        name: &str,
+    ) -> std::io::Result<(String, Frontmatter, PathBuf)> {
+        let path = Self::note_path(notes_dir, name);
 
         if path.exists() {
             let content = fs::read_to_string(&path)?;
-            let mut tags = Vec::new();
-
-            let processed_content = if content.starts_with("---\n") {
-                if let Some(end) = content.find("\n---\n") {
-                    let metadata = &content[4..end];
-                    if let Some(tags_str) = metadata.strip_prefix("tags: ") {
-                        tags = tags_str.split(", ").map(|s| s.to_string()).collect();
-                        content[end + 5..].to_string()
-                    } else {
-                        content
-                    }
-                } else {
-                    content
-                }
-            } else {
-                content
-            };
-
-            Ok((processed_content, tags, path))
+            let (meta, body) = Frontmatter::parse(&content);
+            Ok((body, meta, path))
         } else {
             Err(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -45,10 +50,24 @@ impl FileOperations {
         }
     }
 
-    pub fn save_file(
-        notes_dir: &PathBuf,
+    pub fn load_file(
+        notes_dir: &Path,
+        name: &str,
+    ) -> std::io::Result<(String, Vec<String>, PathBuf)> {
+        let (content, meta, path) = Self::load_note(notes_dir, name)?;
+        Ok((content, meta.tags(), path))
+    }
+
+    pub fn read_meta(path: &Path) -> Option<Frontmatter> {
+        fs::read_to_string(path)
+            .ok()
+            .map(|content| Frontmatter::parse(&content).0)
+    }
+
+    pub fn save_note(
+        notes_dir: &Path,
         content: &str,
-        tags: &[String],
+        meta: &Frontmatter,
         filename: Option<&str>,
     ) -> std::io::Result<PathBuf> {
         let file_path = if let Some(name) = filename {
@@ -58,130 +77,169 @@ impl FileOperations {
             notes_dir.join(format!("note_{}.md", timestamp))
         };
 
-        let mut final_content = String::new();
-        if !tags.is_empty() {
-            final_content.push_str("---\ntags: ");
-            final_content.push_str(&tags.join(", "));
-            final_content.push_str("\n---\n");
+        Self::write_note(&file_path, content, meta)?;
+        Ok(file_path)
+    }
+
+    pub fn write_note(path: &Path, content: &str, meta: &Frontmatter) -> std::io::Result<()> {
+        let mut final_content = meta.render();
+        let content = content.replace("\r\n", "\n");
+        final_content.push_str(&content.replace('\n', meta.newline()));
+        fs::write(path, final_content)
+    }
+
+    pub fn tag_matches(tag: &str, query: &str) -> bool {
+        let query = query.trim_matches('/');
+        tag == query || tag.strip_prefix(query).is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    pub fn expand_tags(tags: &[String]) -> Vec<String> {
+        let mut expanded: Vec<String> = Vec::new();
+        for tag in tags {
+            let ancestors = tag.match_indices('/').map(|(idx, _)| &tag[..idx]);
+            for name in ancestors.chain(std::iter::once(tag.as_str())) {
+                if !expanded.iter().any(|t| t == name) {
+                    expanded.push(name.to_string());
+                }
+            }
+        }
+        expanded
+    }
+
+    pub fn rename_tag(tags: &[String], old: &str, new: Option<&str>) -> Vec<String> {
+        let mut renamed: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = match tag.strip_prefix(old) {
+                Some("") => new.map(str::to_string),
+                Some(rest) if rest.starts_with('/') => new.map(|new| format!("{}{}", new, rest)),
+                _ => Some(tag.clone()),
+            };
+            if let Some(tag) = tag {
+                if !renamed.contains(&tag) {
+                    renamed.push(tag);
+                }
+            }
         }
-        final_content.push_str(content);
+        renamed
+    }
 
-        fs::write(&file_path, final_content)?;
-        Ok(file_path)
+    pub fn retag_notes(notes_dir: &Path, notes: &[String], old: &str, new: &str) -> std::io::Result<usize> {
+        for name in notes {
+            let (content, mut meta, path) = Self::load_note(notes_dir, name)?;
+            meta.set_tags(&Self::rename_tag(&meta.tags(), old, Some(new)));
+            Self::write_note(&path, &content, &meta)?;
+        }
+        Ok(notes.len())
     }
 
-    pub fn list_saved_notes(notes_dir: &PathBuf) -> std::io::Result<Vec<(String, DateTime<Local>, Vec<String>)>> {
+    pub fn list_saved_notes(notes_dir: &Path) -> std::io::Result<Vec<SavedNote>> {
         let mut notes = Vec::new();
 
-        for entry in fs::read_dir(notes_dir)? {
-            let entry = entry?;
-            if entry.path().extension().map_or(false, |ext| ext == "md") {
-                let modified = entry.metadata()?.modified()?;
-                let modified_time = DateTime::<Local>::from(modified);
-
-                let mut tags = Vec::new();
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if content.starts_with("---\n") {
-                        if let Some(end) = content.find("\n---\n") {
-                            let metadata = &content[4..end];
-                            if let Some(tag_list) = metadata.strip_prefix("tags: ") {
-                                tags = tag_list.split(", ").map(|s| s.to_string()).collect();
-                            }
-                        }
-                    }
-                }
+        for path in Self::note_paths(notes_dir)? {
+            let modified = fs::metadata(&path)?.modified()?;
+            let modified_time = DateTime::<Local>::from(modified);
+            let tags = Self::read_meta(&path).map(|meta| meta.tags()).unwrap_or_default();
 
-                let filename = entry
-                    .path()
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .into_owned();
+            let filename = path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned();
 
-                notes.push((filename, modified_time, tags));
-            }
+            notes.push((filename, modified_time, tags));
         }
 
-        notes.sort_by(|a, b| b.1.cmp(&a.1));
+        notes.sort_by_key(|note| std::cmp::Reverse(note.1));
         Ok(notes)
     }
 
-    pub fn get_all_tags(notes_dir: &PathBuf, current_tags: &[String]) -> std::io::Result<(HashSet<String>, HashMap<String, usize>)> {
+    pub fn get_all_tags(
+        notes_dir: &Path,
+        current_file: Option<&Path>,
+        current_tags: &[String],
+    ) -> std::io::Result<(HashSet<String>, HashMap<String, usize>)> {
         let mut all_tags = HashSet::new();
         let mut tag_counts = HashMap::new();
 
-        for tag in current_tags {
-            all_tags.insert(tag.clone());
-            *tag_counts.entry(tag.to_string()).or_insert(0) += 1;
+        for tag in Self::expand_tags(current_tags) {
+            *tag_counts.entry(tag.clone()).or_insert(0) += 1;
+            all_tags.insert(tag);
         }
 
-        for entry in fs::read_dir(notes_dir)? {
-            let entry = entry?;
-            if entry.path().extension().map_or(false, |ext| ext == "md") {
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if content.starts_with("---\n") {
-                        if let Some(end) = content.find("\n---\n") {
-                            let metadata = &content[4..end];
-                            if let Some(tags) = metadata.strip_prefix("tags: ") {
-                                tags.split(", ").for_each(|tag| {
-                                    all_tags.insert(tag.to_string());
-                                    *tag_counts.entry(tag.to_string()).or_insert(0) += 1;
-                                });
-                            }
-                        }
-                    }
-                }
+        for path in Self::note_paths(notes_dir)? {
+            if current_file == Some(path.as_path()) {
+                continue;
+            }
+            let tags = Self::read_meta(&path).map(|meta| meta.tags()).unwrap_or_default();
+            for tag in Self::expand_tags(&tags) {
+                *tag_counts.entry(tag.clone()).or_insert(0) += 1;
+                all_tags.insert(tag);
             }
         }
 
         Ok((all_tags, tag_counts))
     }
 
-    pub fn find_notes_by_tag(notes_dir: &PathBuf, tag: &str) -> std::io::Result<Vec<String>> {
+    pub fn find_notes_by_tag(notes_dir: &Path, tag: &str) -> std::io::Result<Vec<String>> {
         let tag = tag.to_lowercase();
         let mut found_notes = Vec::new();
 
-        for entry in fs::read_dir(notes_dir)? {
-            let entry = entry?;
-            if entry.path().extension().map_or(false, |ext| ext == "md") {
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if content.starts_with("---\n") {
-                        if let Some(end) = content.find("\n---\n") {
-                            let metadata = &content[4..end];
-                            if let Some(tags) = metadata.strip_prefix("tags: ") {
-                                if tags.split(", ").any(|t| t == tag) {
-                                    found_notes.push(
-                                        entry.path().file_name().unwrap().to_string_lossy().into_owned()
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+        for path in Self::note_paths(notes_dir)? {
+            if Self::read_meta(&path).is_some_and(|meta| meta.tags().iter().any(|t| Self::tag_matches(t, &tag))) {
+                found_notes.push(path.file_name().unwrap().to_string_lossy().into_owned());
             }
         }
 
         Ok(found_notes)
     }
 
+    pub fn find_notes_by_meta(notes_dir: &Path, filters: &[MetaFilter]) -> std::io::Result<Vec<String>> {
+        let mut found_notes = Vec::new();
+
+        for path in Self::note_paths(notes_dir)? {
+            let meta = Self::read_meta(&path).unwrap_or_default();
+            if filters.iter().all(|filter| filter.matches(&meta)) {
+                found_notes.push(path.file_name().unwrap().to_string_lossy().into_owned());
+            }
+        }
+
+        Ok(found_notes)
+    }
+
+    pub fn is_http_line(line: &str) -> bool {
+        line.starts_with("HTTP/") || line.starts_with("GET ") || line.starts_with("POST ")
+    }
+
     pub fn format_content(content: &str) -> String {
         let mut formatted = String::new();
         let lines: Vec<&str> = content.lines().collect();
         let mut in_section = false;
+        let mut in_fence = false;
+        let mut after_heading = false;
 
         for line in lines {
-            if line.starts_with("****") {
+            if std::mem::take(&mut after_heading) && !line.is_empty() && line.chars().all(|c| c == '=') {
+                continue;
+            }
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                formatted.push_str(&format!("{}\n", line));
+            } else if in_fence {
+                formatted.push_str(&format!("{}\n", line));
+            } else if line.starts_with("****") {
+                formatted.truncate(formatted.trim_end_matches('\n').len());
+                if !formatted.is_empty() {
+                    formatted.push('\n');
+                }
                 if in_section {
                     formatted.push('\n');
                 }
                 formatted.push_str(&format!("\n{}\n{}\n", line, "=".repeat(line.len())));
                 in_section = true;
+                after_heading = true;
             } else if line.starts_with("- ") {
                 formatted.push_str(&format!("  {}\n", line));
-            } else if line.starts_with("HTTP/")
-                || line.starts_with("GET ")
-                || line.starts_with("POST ")
-            {
+            } else if Self::is_http_line(line) {
                 formatted.push_str(&format!("  > {}\n", line));
             } else if line.is_empty() {
                 formatted.push('\n');
@@ -192,4 +250,16 @@ impl FileOperations {
 
         formatted
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_content_is_idempotent() {
+        let content = "intro\n****Recon\n- host\nGET /admin\n****Findings\n```\n****not a heading\n```\n";
+        let once = FileOperations::format_content(content);
+        assert_eq!(FileOperations::format_content(&once), once);
+        assert_eq!(once.matches("=======").count(), 2);
+    }
+}
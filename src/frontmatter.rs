@@ -0,0 +1,396 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+impl FieldValue {
+    pub fn display(&self) -> String {
+        match self {
+            FieldValue::Scalar(value) => value.clone(),
+            FieldValue::List(items) => items.join(", "),
+        }
+    }
+
+    pub fn contains(&self, value: &str) -> bool {
+        match self {
+            FieldValue::Scalar(field) => field.eq_ignore_ascii_case(value),
+            FieldValue::List(items) => items.iter().any(|item| item.eq_ignore_ascii_case(value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetaFilter {
+    pub key: String,
+    pub value: String,
+}
+
+impl MetaFilter {
+    pub fn parse(expr: &str) -> Result<MetaFilter, String> {
+        match expr.split_once('=') {
+            Some((key, value)) if Frontmatter::valid_key(key.trim()) => Ok(MetaFilter {
+                key: key.trim().to_lowercase(),
+                value: value.trim().to_string(),
+            }),
+            _ => Err(format!("expected key=value, got: {}", expr)),
+        }
+    }
+
+    pub fn matches(&self, meta: &Frontmatter) -> bool {
+        match meta.get(&self.key) {
+            Some(field) => field.contains(&self.value),
+            None => self.value.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: Option<String>,
+    value: FieldValue,
+    raw: Vec<String>,
+    dirty: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Frontmatter {
+    entries: Vec<Entry>,
+    crlf: bool,
+}
+
+impl Frontmatter {
+    pub fn parse(content: &str) -> (Frontmatter, String) {
+        let crlf = content
+            .find('\n')
+            .is_some_and(|idx| content[..idx].ends_with('\r'));
+        let plain = Frontmatter {
+            crlf,
+            ..Frontmatter::default()
+        };
+        if !content.starts_with("---\r\n") && !content.starts_with("---\n") {
+            return (plain, content.to_string());
+        }
+
+        let mut offset = if crlf { 5 } else { 4 };
+        let mut lines = Vec::new();
+        let mut closed = false;
+        for line in content[offset..].split_inclusive('\n') {
+            offset += line.len();
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if line == "---" {
+                closed = true;
+                break;
+            }
+            lines.push(line.to_string());
+        }
+
+        let entries = Self::parse_entries(&lines);
+        if !closed || entries.iter().all(|entry| entry.key.is_none()) {
+            return (plain, content.to_string());
+        }
+
+        (Frontmatter { entries, crlf }, content[offset..].to_string())
+    }
+
+    pub fn valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
+
+    fn parse_key(line: &str) -> Option<(&str, &str)> {
+        let (key, value) = line.split_once(':')?;
+        if Self::valid_key(key) && (value.is_empty() || value.starts_with(' ')) {
+            Some((key, value.trim()))
+        } else {
+            None
+        }
+    }
+
+    fn list_item(line: &str) -> Option<&str> {
+        let trimmed = line.trim_start();
+        if line.len() == trimmed.len() && !line.starts_with('-') {
+            return None;
+        }
+        trimmed
+            .strip_prefix("- ")
+            .or_else(|| (trimmed == "-").then_some(""))
+    }
+
+    fn unquote(value: &str) -> String {
+        let value = value.trim();
+        let quoted = value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')));
+        if quoted {
+            value[1..value.len() - 1].replace("\\\"", "\"")
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn split_items(value: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut current = String::new();
+        let mut quote: Option<char> = None;
+        let mut escaped = false;
+
+        for c in value.chars() {
+            match (quote, c) {
+                (Some('"'), '\\') if !escaped => escaped = true,
+                (Some(q), _) if c == q && !escaped => quote = None,
+                (None, '"' | '\'') if current.trim().is_empty() => quote = Some(c),
+                (None, ',') => {
+                    items.push(Self::unquote(&current));
+                    current.clear();
+                    continue;
+                }
+                _ => escaped = false,
+            }
+            current.push(c);
+        }
+        items.push(Self::unquote(&current));
+
+        items.into_iter().filter(|item| !item.is_empty()).collect()
+    }
+
+    pub fn parse_value(key: &str, value: &str) -> FieldValue {
+        if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            return FieldValue::List(Self::split_items(inner));
+        }
+        if key == "tags" {
+            return FieldValue::List(Self::split_items(value));
+        }
+        FieldValue::Scalar(Self::unquote(value))
+    }
+
+    fn parse_entries(lines: &[String]) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = &lines[i];
+            i += 1;
+
+            let Some((key, value)) = Self::parse_key(line) else {
+                entries.push(Entry {
+                    key: None,
+                    value: FieldValue::Scalar(String::new()),
+                    raw: vec![line.clone()],
+                    dirty: false,
+                });
+                continue;
+            };
+
+            let mut raw = vec![line.clone()];
+            let parsed = if value.is_empty() {
+                let mut items = Vec::new();
+                while let Some(item) = lines.get(i).and_then(|l| Self::list_item(l)) {
+                    items.push(Self::unquote(item));
+                    raw.push(lines[i].clone());
+                    i += 1;
+                }
+                if raw.len() > 1 || key == "tags" {
+                    FieldValue::List(items.into_iter().filter(|item| !item.is_empty()).collect())
+                } else {
+                    FieldValue::Scalar(String::new())
+                }
+            } else {
+                Self::parse_value(key, value)
+            };
+
+            entries.push(Entry {
+                key: Some(key.to_lowercase()),
+                value: parsed,
+                raw,
+                dirty: false,
+            });
+        }
+
+        entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.iter().all(|entry| entry.key.is_none())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&FieldValue> {
+        let key = key.to_lowercase();
+        self.entries
+            .iter()
+            .find(|entry| entry.key.as_deref() == Some(key.as_str()))
+            .map(|entry| &entry.value)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.key.as_deref().map(|key| (key, &entry.value)))
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        match self.get("tags") {
+            Some(FieldValue::List(tags)) => tags.iter().map(|tag| tag.to_lowercase()).collect(),
+            Some(FieldValue::Scalar(tag)) if !tag.is_empty() => vec![tag.to_lowercase()],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: FieldValue) {
+        let key = key.to_lowercase();
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.key.as_deref() == Some(key.as_str()))
+        {
+            Some(entry) => {
+                if entry.value != value {
+                    entry.value = value;
+                    entry.dirty = true;
+                }
+            }
+            None => self.entries.push(Entry {
+                key: Some(key),
+                value,
+                raw: Vec::new(),
+                dirty: true,
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        let before = self.entries.len();
+        self.entries
+            .retain(|entry| entry.key.as_deref() != Some(key.as_str()));
+        self.entries.len() != before
+    }
+
+    pub fn set_tags(&mut self, tags: &[String]) {
+        if tags.is_empty() {
+            self.remove("tags");
+        } else if self.tags() != tags {
+            self.set("tags", FieldValue::List(tags.to_vec()));
+        }
+    }
+
+    fn quote(value: &str) -> String {
+        let needs_quotes = value.contains(": ")
+            || value.contains(" #")
+            || value.contains(',')
+            || value != value.trim()
+            || value.starts_with(['[', '{', '"', '\'', '#', '&', '*', '!', '|', '>', '%', '@', '`', '-']);
+        if needs_quotes {
+            format!("\"{}\"", value.replace('"', "\\\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn format_entry(key: &str, value: &FieldValue) -> String {
+        match value {
+            FieldValue::Scalar(value) if value.contains(',') => format!("{}: {}", key, Self::quote(value)),
+            FieldValue::Scalar(value) if value.is_empty() => format!("{}:", key),
+            FieldValue::Scalar(value) => format!("{}: {}", key, Self::quote(value)),
+            FieldValue::List(items) if key == "tags" => format!("tags: {}", items.join(", ")),
+            FieldValue::List(items) => format!(
+                "{}: [{}]",
+                key,
+                items.iter().map(|item| Self::quote(item)).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    pub fn newline(&self) -> &'static str {
+        if self.crlf {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    pub fn render(&self) -> String {
+        if self.entries.is_empty() {
+            return String::new();
+        }
+
+        let newline = self.newline();
+        let mut out = format!("---{}", newline);
+        for entry in &self.entries {
+            match &entry.key {
+                Some(key) if entry.dirty || entry.raw.is_empty() => {
+                    out.push_str(&Self::format_entry(key, &entry.value));
+                    out.push_str(newline);
+                }
+                _ => {
+                    for line in &entry.raw {
+                        out.push_str(line);
+                        out.push_str(newline);
+                    }
+                }
+            }
+        }
+        out.push_str("---");
+        out.push_str(newline);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(content: &str) -> String {
+        let (meta, body) = Frontmatter::parse(content);
+        format!("{}{}", meta.render(), body)
+    }
+
+    #[test]
+    fn block_without_keys_is_body() {
+        let content = "---\nintro paragraph\n---\nbody line\n";
+        let (meta, body) = Frontmatter::parse(content);
+        assert!(meta.is_empty());
+        assert_eq!(body, content);
+        assert_eq!(round_trip(content), content);
+    }
+
+    #[test]
+    fn keyless_lines_survive_round_trip() {
+        let content = "---\n# reviewed\ntitle: Recon\n---\nbody\n";
+        let (mut meta, body) = Frontmatter::parse(content);
+        assert_eq!(body, "body\n");
+        meta.remove("title");
+        assert_eq!(meta.render(), "---\n# reviewed\n---\n");
+    }
+
+    #[test]
+    fn crlf_is_detected_and_kept() {
+        let content = "---\r\ntitle: Recon\r\n---\r\nbody\r\n";
+        let (mut meta, body) = Frontmatter::parse(content);
+        assert_eq!(meta.newline(), "\r\n");
+        assert_eq!(round_trip(content), content);
+
+        meta.set_tags(&["web".to_string()]);
+        assert_eq!(meta.render(), "---\r\ntitle: Recon\r\ntags: web\r\n---\r\n");
+        assert_eq!(body, "body\r\n");
+
+        let (meta, _) = Frontmatter::parse("plain\r\nbody\r\n");
+        assert_eq!(meta.newline(), "\r\n");
+    }
+
+    #[test]
+    fn quoted_list_items_keep_commas() {
+        let mut meta = Frontmatter::default();
+        let items = vec!["x".to_string(), "a, b".to_string(), "say \"hi\", then".to_string()];
+        meta.set("hosts", FieldValue::List(items.clone()));
+
+        let (parsed, _) = Frontmatter::parse(&format!("{}body\n", meta.render()));
+        assert_eq!(parsed.get("hosts"), Some(&FieldValue::List(items)));
+        assert_eq!(
+            Frontmatter::parse_value("tags", "web, 'a, b'"),
+            FieldValue::List(vec!["web".to_string(), "a, b".to_string()])
+        );
+    }
+}
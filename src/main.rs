@@ -1,28 +1,134 @@
+mod aliases;
+mod cli;
 mod commands;
+mod completion;
+mod config;
 mod editor;
+mod export;
+mod extract;
 mod file_ops;
+mod frontmatter;
+mod pdf;
+mod quick;
+mod search;
+mod session;
 mod stats;
+mod templates;
 
-use commands::CommandParser;
+use aliases::Aliases;
+use cli::Cli;
+use completion::NoteHelper;
+use config::Config;
 use editor::Editor;
 use rustyline::error::ReadlineError;
-use rustyline::Editor as LineEditor;
-use std::io;
+use session::Session;
+use rustyline::{CompletionType, Config as LineConfig, Editor as LineEditor};
+use std::io::{self, IsTerminal};
+
+const HISTORY_FILE: &str = ".rustynotes_history";
+const RC_FILE: &str = ".rustynotesrc";
+
+fn run_batch(editor: &mut Editor) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if io::stdin().read_line(&mut line)? == 0 {
+            break;
+        }
+        if !editor.run_line(&line)? {
+            break;
+        }
+    }
+    Ok(())
+}
 
 fn main() -> io::Result<()> {
-    let mut editor = Editor::new()?;
-    let mut line_editor = LineEditor::<(), _>::new().map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    let args = match Cli::parse_global(std::env::args().skip(1).collect()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("rustynotes: {}", e);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("rustynotes: bad config file {}", e);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+    let (notes_dir, vault) = match config.resolve_notes_dir(args.dir.as_deref(), args.vault.as_deref()) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("rustynotes: {}", e);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+
+    let mut editor = Editor::new(notes_dir)?;
+    editor.vaults = config.vault_dirs();
+    editor.current_vault = vault;
+    if let Some(default_dir) = config.default_notes_dir() {
+        editor.default_dir = default_dir;
+    }
+    let (aliases, warnings) = Aliases::from_config(&config);
+    for warning in warnings {
+        eprintln!("rustynotes: config: {}", warning);
+    }
+    editor.aliases = aliases;
+
+    if !args.rest.is_empty() {
+        let code = Cli::run(&mut editor, &args.rest)?;
+        std::process::exit(code);
+    }
+
+    if !io::stdin().is_terminal() {
+        return run_batch(&mut editor);
+    }
+
+    let line_config = LineConfig::builder()
+        .max_history_size(config.history_size())
+        .and_then(|builder| builder.history_ignore_dups(true))
+        .map_err(io::Error::other)?
+        .history_ignore_space(true)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut line_editor = LineEditor::<NoteHelper, _>::with_config(line_config).map_err(io::Error::other)?;
+    line_editor.set_helper(Some(NoteHelper::new(editor.notes_dir.clone())));
+
+    let history_path = editor.notes_dir.join(HISTORY_FILE);
+    if history_path.exists() {
+        if let Err(e) = line_editor.load_history(&history_path) {
+            println!("[-] could not load history: {}", e);
+        }
+    }
 
     println!("rustynotes: a simple cli note-taking tool");
     println!("type :help for commands\n");
 
+    let session_dir = editor.notes_dir.clone();
+    let rc_path = editor.notes_dir.join(RC_FILE);
+    editor.restore_session(&session_dir)?;
+    if rc_path.exists() && !editor.source_file(&rc_path)? {
+        return Ok(());
+    }
+
     loop {
+        if let Some(helper) = line_editor.helper_mut() {
+            helper.notes_dir.clone_from(&editor.notes_dir);
+            helper.current_tags.clone_from(&editor.current_tags);
+            helper.vaults = editor.vaults.keys().cloned().collect();
+            helper.aliases = editor.aliases.names();
+            helper.in_multi_line = editor.in_multi_line;
+        }
         let prompt = if editor.in_multi_line { " " } else { ":> " };
         match line_editor.readline(prompt) {
             Ok(line) => {
-                let _ = line_editor.add_history_entry(line.as_str());
-                let command = CommandParser::parse(&line, editor.in_multi_line, editor.edit_mode);
-                if !editor.execute_command(command)? {
+                if !editor.in_multi_line && !line.trim().is_empty() {
+                    let _ = line_editor.add_history_entry(line.as_str());
+                }
+                if !editor.run_line(&line)? {
                     break;
                 }
             }
@@ -40,5 +146,14 @@ fn main() -> io::Result<()> {
             }
         }
     }
+
+    if let Err(e) = line_editor.save_history(&history_path) {
+        println!("[-] could not save history: {}", e);
+    }
+    match Session::capture(&editor).save(&session_dir) {
+        Ok(true) if editor.modified => println!("[+] unsaved changes kept for the next session"),
+        Ok(_) => {}
+        Err(e) => println!("[-] could not save session: {}", e),
+    }
     Ok(())
 }
\ No newline at end of file
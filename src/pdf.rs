@@ -0,0 +1,367 @@
+use crate::export::ExportNote;
+use crate::file_ops::FileOperations;
+use chrono::Local;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const BODY_SIZE: f32 = 10.0;
+const MONO_SIZE: f32 = 8.5;
+const HEADER_SIZE: f32 = 8.0;
+
+// glyph widths for ascii 32..=126, in 1/1000 em (from the standard afm metrics)
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    fn char_width(&self, c: char, size: f32) -> f32 {
+        let units = match self {
+            Font::Mono => 600,
+            Font::Regular | Font::Bold => {
+                let table = if *self == Font::Bold {
+                    &HELVETICA_BOLD_WIDTHS
+                } else {
+                    &HELVETICA_WIDTHS
+                };
+                match c as u32 {
+                    code @ 32..=126 => table[(code - 32) as usize],
+                    _ => 556,
+                }
+            }
+        };
+        units as f32 * size / 1000.0
+    }
+
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        text.chars().map(|c| self.char_width(c, size)).sum()
+    }
+}
+
+pub struct PdfDocument {
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+    header: String,
+}
+
+impl PdfDocument {
+    fn new() -> Self {
+        PdfDocument {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+            header: String::new(),
+        }
+    }
+
+    fn new_page(&mut self) {
+        if !self.current.is_empty() {
+            self.finish_page();
+        }
+        self.y = PAGE_HEIGHT - MARGIN;
+        if !self.header.is_empty() {
+            let header = self.header.clone();
+            self.draw_text(Font::Regular, HEADER_SIZE, MARGIN, PAGE_HEIGHT - MARGIN / 2.0, &header, 0.5);
+        }
+    }
+
+    fn finish_page(&mut self) {
+        let footer = format!("page {}", self.pages.len() + 1);
+        let x = PAGE_WIDTH - MARGIN - Font::Regular.text_width(&footer, HEADER_SIZE);
+        self.draw_text(Font::Regular, HEADER_SIZE, x, MARGIN / 2.0, &footer, 0.5);
+        self.pages.push(std::mem::take(&mut self.current));
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.current.is_empty() || self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn draw_text(&mut self, font: Font, size: f32, x: f32, y: f32, text: &str, gray: f32) {
+        self.current.push_str(&format!(
+            "{:.2} g BT /{} {:.1} Tf {:.2} {:.2} Td ({}) Tj ET 0 g\n",
+            gray,
+            font.resource(),
+            size,
+            x,
+            y,
+            Self::escape(text)
+        ));
+    }
+
+    fn line(&mut self, font: Font, size: f32, indent: f32, text: &str, gray: f32) {
+        let leading = size * 1.4;
+        self.ensure_space(leading);
+        self.y -= leading;
+        let y = self.y;
+        self.draw_text(font, size, MARGIN + indent, y, text, gray);
+    }
+
+    fn space(&mut self, height: f32) {
+        if !self.current.is_empty() {
+            self.y -= height;
+        }
+    }
+
+    fn paragraph(&mut self, font: Font, size: f32, indent: f32, text: &str) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+        for line in Self::wrap(font, size, width, text) {
+            self.line(font, size, indent, &line, 0.0);
+        }
+    }
+
+    fn mono_line(&mut self, text: &str) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN;
+        let leading = MONO_SIZE * 1.4;
+        let max_chars = ((width - 8.0) / Font::Mono.char_width(' ', MONO_SIZE)) as usize;
+        let chars: Vec<char> = text.replace('\t', "    ").chars().collect();
+        let chunks: Vec<String> = if chars.is_empty() {
+            vec![String::new()]
+        } else {
+            chars.chunks(max_chars.max(1)).map(|c| c.iter().collect()).collect()
+        };
+
+        for chunk in chunks {
+            self.ensure_space(leading);
+            self.current.push_str(&format!(
+                "0.94 g {:.2} {:.2} {:.2} {:.2} re f 0 g\n",
+                MARGIN,
+                self.y - leading,
+                width,
+                leading
+            ));
+            self.y -= leading;
+            let y = self.y + MONO_SIZE * 0.35;
+            self.draw_text(Font::Mono, MONO_SIZE, MARGIN + 4.0, y, &chunk, 0.0);
+        }
+    }
+
+    fn wrap(font: Font, size: f32, width: f32, text: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+
+        for word in text.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if font.text_width(&candidate, size) <= width {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            for c in word.chars() {
+                if font.text_width(&current, size) + font.char_width(c, size) > width && !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+                current.push(c);
+            }
+        }
+        if !current.is_empty() || lines.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '(' | ')' | '\\' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                ' '..='~' => escaped.push(c),
+                '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", c as u32)),
+                '\u{2018}' | '\u{2019}' => escaped.push('\''),
+                '\u{201c}' | '\u{201d}' => escaped.push('"'),
+                '\u{2013}' | '\u{2014}' => escaped.push('-'),
+                _ => escaped.push('?'),
+            }
+        }
+        escaped
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if !self.current.is_empty() {
+            self.finish_page();
+        }
+
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_string(),
+        ];
+
+        let mut kids = Vec::new();
+        for content in &self.pages {
+            let page_id = objects.len() + 1;
+            kids.push(format!("{} 0 R", page_id));
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ));
+        }
+        objects[1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            self.pages.len()
+        );
+
+        let mut out: Vec<u8> = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", idx + 1, object).as_bytes());
+        }
+
+        let xref_start = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_start
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+pub struct PdfRenderer;
+
+impl PdfRenderer {
+    pub fn render(notes: &[ExportNote]) -> Vec<u8> {
+        let mut doc = PdfDocument::new();
+
+        doc.new_page();
+        doc.line(Font::Bold, 22.0, 0.0, "rustynotes export", 0.0);
+        doc.line(
+            Font::Regular,
+            BODY_SIZE,
+            0.0,
+            &format!("exported {} - {} note(s)", Local::now().format("%Y-%m-%d %H:%M"), notes.len()),
+            0.4,
+        );
+        doc.space(12.0);
+        doc.line(Font::Bold, 14.0, 0.0, "Contents", 0.0);
+        for (idx, note) in notes.iter().enumerate() {
+            doc.paragraph(Font::Regular, BODY_SIZE, 8.0, &format!("{}. {}", idx + 1, note.name));
+        }
+
+        for note in notes {
+            doc.header = if note.tags.is_empty() {
+                note.name.clone()
+            } else {
+                format!("{}  [{}]", note.name, note.tags.join(", "))
+            };
+            doc.new_page();
+            Self::render_note(&mut doc, note);
+        }
+
+        doc.finish()
+    }
+
+    fn render_note(doc: &mut PdfDocument, note: &ExportNote) {
+        doc.line(Font::Bold, 18.0, 0.0, note.name.trim_end_matches(".md"), 0.0);
+        let mut meta = format!("modified: {}", note.modified.format("%Y-%m-%d %H:%M"));
+        if !note.tags.is_empty() {
+            meta.push_str(&format!("    tags: {}", note.tags.join(", ")));
+        }
+        doc.line(Font::Regular, 9.0, 0.0, &meta, 0.4);
+        doc.space(8.0);
+
+        let mut in_code = false;
+        let mut in_http = false;
+        let mut after_heading = false;
+
+        for line in note.content.lines() {
+            let trimmed = line.trim_start();
+
+            if trimmed.starts_with("```") {
+                in_code = !in_code;
+                in_http = false;
+                continue;
+            }
+            if in_code {
+                doc.mono_line(line);
+                continue;
+            }
+
+            let request = trimmed.strip_prefix("> ").unwrap_or(trimmed);
+            if FileOperations::is_http_line(request) {
+                in_http = true;
+                doc.mono_line(request);
+                continue;
+            }
+            if in_http && !trimmed.is_empty() {
+                doc.mono_line(trimmed);
+                continue;
+            }
+            in_http = false;
+
+            if after_heading && trimmed.len() >= 3 && trimmed.chars().all(|c| c == '=') {
+                after_heading = false;
+                continue;
+            }
+            after_heading = false;
+
+            if let Some(heading) = trimmed.strip_prefix("****") {
+                doc.space(6.0);
+                doc.paragraph(Font::Bold, 13.0, 0.0, heading.trim_start_matches('*').trim());
+                after_heading = true;
+            } else if let Some(item) = trimmed.strip_prefix("- ") {
+                doc.paragraph(Font::Regular, BODY_SIZE, 10.0, &format!("- {}", item));
+            } else if trimmed.is_empty() {
+                doc.space(BODY_SIZE * 0.7);
+            } else {
+                doc.paragraph(Font::Regular, BODY_SIZE, 0.0, trimmed);
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::file_ops::FileOperations;
+use crate::frontmatter::Frontmatter;
+use chrono::Local;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default)]
+pub struct QuickOptions {
+    pub text: String,
+    pub save: Option<String>,
+    pub add: Option<String>,
+    pub tags: Vec<String>,
+    pub timestamp: bool,
+    pub template: Option<String>,
+    pub fence: Option<String>,
+    pub stdin: bool,
+}
+
+impl QuickOptions {
+    pub fn parse(args: &[String]) -> Result<QuickOptions, String> {
+        let mut options = QuickOptions::default();
+        let mut text = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let mut value = || {
+                iter.next()
+                    .cloned()
+                    .ok_or_else(|| format!("{} requires a value", arg))
+            };
+            match arg.as_str() {
+                "--save" => options.save = Some(value()?),
+                "--add" => options.add = Some(value()?),
+                "--template" => options.template = Some(value()?),
+                "--fence" => options.fence = Some(value()?),
+                "--tag" => options.tags.extend(
+                    value()?
+                        .split(',')
+                        .map(|tag| tag.trim().to_lowercase())
+                        .filter(|tag| !tag.is_empty()),
+                ),
+                "--timestamp" => options.timestamp = true,
+                flag if flag.starts_with("--") => return Err(format!("unknown quick option: {}", flag)),
+                _ => text.push(arg.clone()),
+            }
+        }
+
+        if text.is_empty() {
+            return Err("text required: :quick \"text\" [--save name | --add note]".to_string());
+        }
+        if options.save.is_some() && options.add.is_some() {
+            return Err("use either --save or --add, not both".to_string());
+        }
+        if options.add.is_some() && options.template.is_some() {
+            return Err("--template only works when creating a note".to_string());
+        }
+
+        options.stdin = text.len() == 1 && text[0] == "-";
+        options.text = text.join(" ");
+        Ok(options)
+    }
+
+    pub fn entry(&self) -> io::Result<String> {
+        if self.stdin {
+            let text = QuickCapture::read_stdin()?;
+            Ok(QuickCapture::stdin_block(&text, self.fence.as_deref()))
+        } else if let Some(lang) = &self.fence {
+            Ok(format!("```{}\n{}\n```\n", lang, self.text))
+        } else {
+            Ok(self.text.clone())
+        }
+    }
+
+    pub fn note_name(&self) -> Option<String> {
+        let name = self.save.as_ref()?.trim_end_matches(".md").to_string();
+        if self.timestamp {
+            Some(format!("{}_{}", Local::now().format("%Y-%m-%d"), name))
+        } else {
+            Some(name)
+        }
+    }
+}
+
+pub struct QuickCapture;
+
+impl QuickCapture {
+    pub fn read_stdin() -> io::Result<String> {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    pub fn stdin_block(text: &str, fence: Option<&str>) -> String {
+        let mut block = format!("****{}\n", Local::now().format("%Y-%m-%d %H:%M:%S"));
+        if let Some(lang) = fence {
+            block.push_str(&format!("```{}\n", lang));
+        }
+        block.push_str(text.trim_end_matches('\n'));
+        block.push('\n');
+        if fence.is_some() {
+            block.push_str("```\n");
+        }
+        block
+    }
+
+    fn merge_tags(existing: &mut Vec<String>, tags: &[String]) {
+        for tag in tags {
+            if !existing.contains(tag) {
+                existing.push(tag.clone());
+            }
+        }
+    }
+
+    pub fn append(notes_dir: &Path, name: &str, text: &str, tags: &[String]) -> io::Result<PathBuf> {
+        let (mut content, mut meta, path) = FileOperations::load_note(notes_dir, name)?;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&FileOperations::format_content(text));
+        meta.set_tags(&Self::merged_tags(&meta.tags(), tags));
+
+        let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+        FileOperations::save_note(notes_dir, &content, &meta, Some(&stem))
+    }
+
+    pub fn create(
+        notes_dir: &Path,
+        name: Option<&str>,
+        body: &str,
+        text: &str,
+        meta: &Frontmatter,
+    ) -> io::Result<PathBuf> {
+        if let Some(name) = name {
+            if notes_dir.join(format!("{}.md", name)).exists() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{}.md already exists, use --add to append", name),
+                ));
+            }
+        }
+
+        let mut content = body.to_string();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(text);
+        FileOperations::save_note(notes_dir, &FileOperations::format_content(&content), meta, name)
+    }
+
+    pub fn merged_tags(template_tags: &[String], tags: &[String]) -> Vec<String> {
+        let mut merged = template_tags.to_vec();
+        Self::merge_tags(&mut merged, tags);
+        merged
+    }
+}
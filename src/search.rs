@@ -0,0 +1,396 @@
+use crate::extract::Extractor;
+use crate::file_ops::FileOperations;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+const HIGHLIGHT_START: &str = "\x1b[1;31m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+const MIN_WORD_LEN: usize = 3;
+const PREVIEW_CONTEXT: usize = 2;
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub term: String,
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub whole_word: bool,
+    pub exclude: Vec<String>,
+    pub all_notes: bool,
+    pub extract: Option<Extractor>,
+    pub before: Option<usize>,
+    pub after: Option<usize>,
+}
+
+impl SearchQuery {
+    pub fn parse(args: &[String]) -> Result<SearchQuery, String> {
+        let (mut query, terms) = Self::parse_flags(args)?;
+        if terms.is_empty() && query.extract.is_none() {
+            return Err("search term required".to_string());
+        }
+        query.term = terms.join(" ");
+        Ok(query)
+    }
+
+    pub fn parse_flags(args: &[String]) -> Result<(SearchQuery, Vec<String>), String> {
+        let mut query = SearchQuery::default();
+        let mut terms = Vec::new();
+        let mut iter = args.iter().peekable();
+        let mut flags_done = false;
+
+        while let Some(arg) = iter.next() {
+            if flags_done || !arg.starts_with('-') || arg == "-" {
+                terms.push(arg.clone());
+                continue;
+            }
+            match arg.as_str() {
+                "--" => flags_done = true,
+                "-r" | "--regex" => query.regex = true,
+                "-i" | "--ignore-case" => query.ignore_case = true,
+                "-w" | "--word" => query.whole_word = true,
+                "-a" | "--all" => query.all_notes = true,
+                "--urls" => query.extract = Some(Extractor::Urls),
+                "--headers" => query.extract = Some(Extractor::Headers),
+                "--ips" => query.extract = Some(Extractor::Ips),
+                "--emails" => query.extract = Some(Extractor::Emails),
+                "--code" => {
+                    let lang = iter.next_if(|next| !next.starts_with('-')).cloned();
+                    query.extract = Some(Extractor::Code(lang));
+                }
+                "-C" | "--context" => {
+                    let n = Self::parse_count(arg, iter.next())?;
+                    query.before = Some(n);
+                    query.after = Some(n);
+                }
+                "-B" | "--before" => query.before = Some(Self::parse_count(arg, iter.next())?),
+                "-A" | "--after" => query.after = Some(Self::parse_count(arg, iter.next())?),
+                "--preview" => {
+                    query.before.get_or_insert(PREVIEW_CONTEXT);
+                    query.after.get_or_insert(PREVIEW_CONTEXT);
+                }
+                "--not" => match iter.next() {
+                    Some(term) => query.exclude.push(term.clone()),
+                    None => return Err("--not requires a term".to_string()),
+                },
+                _ => return Err(format!("unknown search flag: {}", arg)),
+            }
+        }
+
+        Ok((query, terms))
+    }
+
+    fn parse_count(flag: &str, value: Option<&String>) -> Result<usize, String> {
+        value
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("{} requires a number of lines", flag))
+    }
+
+    pub fn context(&self, default: usize) -> (usize, usize) {
+        (self.before.unwrap_or(default), self.after.unwrap_or(default))
+    }
+
+    fn build_regex(&self, term: &str) -> Result<Regex, String> {
+        let mut pattern = if self.regex {
+            term.to_string()
+        } else {
+            regex::escape(term)
+        };
+        if self.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+            .map_err(|e| format!("invalid pattern '{}': {}", term, e))
+    }
+
+    pub fn compile(&self) -> Result<Matcher, String> {
+        let pattern = self.build_regex(&self.term)?;
+        let exclude = self
+            .exclude
+            .iter()
+            .map(|term| self.build_regex(term))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Matcher {
+            pattern,
+            exclude,
+            color: io::stdout().is_terminal(),
+        })
+    }
+}
+
+pub struct Matcher {
+    pub pattern: Regex,
+    exclude: Vec<Regex>,
+    color: bool,
+}
+
+impl Matcher {
+    pub fn is_match(&self, line: &str) -> bool {
+        self.pattern.is_match(line) && !self.exclude.iter().any(|re| re.is_match(line))
+    }
+
+    pub fn replace(&self, line: &str, replacement: &str, expand: bool) -> String {
+        if expand {
+            self.pattern.replace_all(line, replacement).into_owned()
+        } else {
+            self.pattern
+                .replace_all(line, regex::NoExpand(replacement))
+                .into_owned()
+        }
+    }
+
+    pub fn check_replacement(&self, replacement: &str) -> Result<(), String> {
+        let mut rest = replacement;
+        while let Some(idx) = rest.find('$') {
+            rest = &rest[idx + 1..];
+            if let Some(after) = rest.strip_prefix('$') {
+                rest = after;
+                continue;
+            }
+            let (name, after) = match rest.strip_prefix('{').and_then(|r| r.split_once('}')) {
+                Some((name, after)) => (name, after),
+                None => {
+                    let end = rest
+                        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                        .unwrap_or(rest.len());
+                    (&rest[..end], &rest[end..])
+                }
+            };
+            rest = after;
+            if name.is_empty() {
+                continue;
+            }
+
+            let exists = match name.parse::<usize>() {
+                Ok(idx) => idx < self.pattern.captures_len(),
+                Err(_) => self.pattern.capture_names().flatten().any(|group| group == name),
+            };
+            if !exists {
+                return Err(format!(
+                    "no group '{}' in the pattern, write ${{1}}A to put text right after a group",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn highlight(&self, line: &str) -> String {
+        if !self.color {
+            return line.to_string();
+        }
+        let mut out = String::new();
+        let mut last = 0;
+        for m in self.pattern.find_iter(line) {
+            if m.start() == m.end() {
+                continue;
+            }
+            out.push_str(&line[last..m.start()]);
+            out.push_str(HIGHLIGHT_START);
+            out.push_str(m.as_str());
+            out.push_str(HIGHLIGHT_END);
+            last = m.end();
+        }
+        out.push_str(&line[last..]);
+        out
+    }
+}
+
+pub struct NoteMatches {
+    pub filename: String,
+    pub lines: Vec<String>,
+    pub hits: Vec<usize>,
+}
+
+pub struct Searcher;
+
+impl Searcher {
+    pub fn matching_lines(content: &str, matcher: &Matcher) -> Vec<usize> {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| matcher.is_match(line))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn search_notes(notes_dir: &Path, matcher: &Matcher) -> io::Result<Vec<NoteMatches>> {
+        let mut results = Vec::new();
+
+        for (filename, _, _) in FileOperations::list_saved_notes(notes_dir)? {
+            let content = match FileOperations::load_file(notes_dir, &filename) {
+                Ok((content, _, _)) => content,
+                Err(_) => continue,
+            };
+
+            let hits = Self::matching_lines(&content, matcher);
+            if !hits.is_empty() {
+                results.push(NoteMatches {
+                    filename,
+                    lines: content.lines().map(String::from).collect(),
+                    hits,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub fn display_note_matches(idx: usize, matches: &NoteMatches, matcher: &Matcher, context: (usize, usize)) {
+        println!(
+            "{:2}. {} ({} match{})",
+            idx,
+            matches.filename,
+            matches.hits.len(),
+            if matches.hits.len() == 1 { "" } else { "es" }
+        );
+        Self::display_lines(&matches.lines, &matches.hits, matcher, context, "    ");
+    }
+
+    pub fn display_lines<S: AsRef<str>>(
+        lines: &[S],
+        hits: &[usize],
+        matcher: &Matcher,
+        (before, after): (usize, usize),
+        indent: &str,
+    ) {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for &hit in hits {
+            let start = hit.saturating_sub(before);
+            let end = (hit + after).min(lines.len().saturating_sub(1));
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => ranges.push((start, end)),
+            }
+        }
+
+        for (n, &(start, end)) in ranges.iter().enumerate() {
+            if n > 0 && (before > 0 || after > 0) {
+                println!("{}  --", indent);
+            }
+            for (i, line) in lines.iter().enumerate().take(end + 1).skip(start) {
+                let line = line.as_ref();
+                if hits.contains(&i) {
+                    println!("{}{:>4}: {}", indent, i + 1, matcher.highlight(line));
+                } else {
+                    println!("{}{:>4}  {}", indent, i + 1, line);
+                }
+            }
+        }
+    }
+
+    pub fn build_vocabulary(notes_dir: &Path, current: &str) -> io::Result<HashMap<String, usize>> {
+        let mut vocabulary = HashMap::new();
+        let mut add_words = |text: &str| {
+            for word in Self::words(text) {
+                *vocabulary.entry(word).or_insert(0) += 1;
+            }
+        };
+
+        add_words(current);
+        for path in FileOperations::note_paths(notes_dir)? {
+            if let Ok(content) = std::fs::read_to_string(&path) {
+                add_words(&content);
+            }
+        }
+
+        Ok(vocabulary)
+    }
+
+    fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+            .map(|w| w.trim_matches(|c| c == '-' || c == '_'))
+            .filter(|w| w.chars().count() >= MIN_WORD_LEN)
+            .map(|w| w.to_lowercase())
+    }
+
+    pub fn suggest(term: &str, vocabulary: &HashMap<String, usize>, limit: usize) -> Vec<String> {
+        let words: Vec<String> = term.split_whitespace().map(|w| w.to_lowercase()).collect();
+
+        if words.len() == 1 {
+            return Self::closest_words(&words[0], vocabulary, limit);
+        }
+
+        let mut changed = false;
+        let corrected: Vec<String> = words
+            .iter()
+            .map(|word| {
+                if vocabulary.contains_key(word) {
+                    return word.clone();
+                }
+                match Self::closest_words(word, vocabulary, 1).pop() {
+                    Some(best) => {
+                        changed = true;
+                        best
+                    }
+                    None => word.clone(),
+                }
+            })
+            .collect();
+
+        if changed {
+            vec![corrected.join(" ")]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn closest_words(word: &str, vocabulary: &HashMap<String, usize>, limit: usize) -> Vec<String> {
+        let len = word.chars().count();
+        let max_distance = (len / 3).max(1);
+
+        let mut candidates: Vec<(usize, usize, &String)> = vocabulary
+            .iter()
+            .filter(|(candidate, _)| candidate.as_str() != word)
+            .filter(|(candidate, _)| candidate.chars().count().abs_diff(len) <= max_distance)
+            .map(|(candidate, &count)| (Self::edit_distance(word, candidate), count, candidate))
+            .filter(|(distance, _, _)| *distance <= max_distance)
+            .collect();
+
+        candidates.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(b.2)));
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, candidate)| candidate.clone())
+            .collect()
+    }
+
+    fn edit_distance(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let mut prev: Vec<usize> = (0..=b.len()).collect();
+        let mut curr = vec![0; b.len() + 1];
+
+        for i in 1..=a.len() {
+            curr[0] = i;
+            for j in 1..=b.len() {
+                let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+                curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            }
+            std::mem::swap(&mut prev, &mut curr);
+        }
+
+        prev[b.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(args: &[&str]) -> Matcher {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        SearchQuery::parse(&args).unwrap().compile().unwrap()
+    }
+
+    #[test]
+    fn replacement_groups_must_exist() {
+        let matcher = matcher(&["--regex", r"(\w+)a$"]);
+        assert!(matcher.check_replacement("$1a").is_err());
+        assert!(matcher.check_replacement("${1}a").is_ok());
+        assert!(matcher.check_replacement("$0 $$2").is_ok());
+        assert_eq!(matcher.replace("fooa", "${1}A", true), "fooA");
+    }
+}
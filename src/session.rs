@@ -0,0 +1,145 @@
+use crate::editor::Editor;
+use crate::file_ops::FileOperations;
+use crate::frontmatter::Frontmatter;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SESSION_FILE: &str = ".rustynotes_session.toml";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub notes_dir: PathBuf,
+    pub vault: Option<String>,
+    pub current_file: Option<PathBuf>,
+    pub content: String,
+    pub modified: bool,
+    pub tags: Vec<String>,
+    pub meta: String,
+    pub edit_mode: bool,
+    pub edit_buffer: Vec<String>,
+    pub in_multi_line: bool,
+    pub current_block: String,
+}
+
+impl Session {
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(SESSION_FILE)
+    }
+
+    pub fn capture(editor: &Editor) -> Session {
+        Session {
+            notes_dir: editor.notes_dir.clone(),
+            vault: editor.current_vault.clone(),
+            current_file: editor.current_file.clone(),
+            content: editor.content.clone(),
+            modified: editor.modified,
+            tags: editor.current_tags.clone(),
+            meta: editor.note_meta().render(),
+            edit_mode: editor.edit_mode,
+            edit_buffer: editor.edit_buffer.clone(),
+            in_multi_line: editor.in_multi_line,
+            current_block: editor.current_block.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.modified
+            && self.current_file.is_none()
+            && self.content.is_empty()
+            && self.current_block.is_empty()
+            && self.tags.is_empty()
+            && self.meta.is_empty()
+            && self.edit_buffer.is_empty()
+    }
+
+    pub fn load(dir: &Path) -> io::Result<Option<Session>> {
+        let path = Self::path(dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(&path)?;
+        toml::from_str(&raw).map(Some).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e.message()),
+            )
+        })
+    }
+
+    pub fn save(&self, dir: &Path) -> io::Result<bool> {
+        if self.is_empty() {
+            return Self::clear(dir).map(|_| false);
+        }
+        let raw = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(Self::path(dir), raw)?;
+        Ok(true)
+    }
+
+    pub fn clear(dir: &Path) -> io::Result<()> {
+        match fs::remove_file(Self::path(dir)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let name = self
+            .current_file
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map_or_else(|| "[not saved]".to_string(), |name| name.to_string_lossy().into_owned());
+
+        let mut state = Vec::new();
+        if self.modified {
+            state.push("unsaved changes".to_string());
+        }
+        if self.edit_mode {
+            state.push("edit mode".to_string());
+        }
+        if self.in_multi_line {
+            state.push("multi-line input".to_string());
+        }
+        if let Some(vault) = &self.vault {
+            state.push(format!("vault {}", vault));
+        }
+
+        if state.is_empty() {
+            name
+        } else {
+            format!("{} ({})", name, state.join(", "))
+        }
+    }
+
+    pub fn restore(self, editor: &mut Editor) {
+        if self.notes_dir.is_dir() {
+            editor.notes_dir = self.notes_dir;
+            editor.current_vault = self.vault;
+        }
+        editor.current_file = self.current_file;
+        editor.content = self.content;
+        editor.modified = self.modified;
+        editor.current_tags = self.tags;
+        editor.current_meta = Frontmatter::parse(&self.meta).0;
+        if let (false, Some(path)) = (self.modified, &editor.current_file) {
+            let dir = path.parent().unwrap_or(Path::new("."));
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            match FileOperations::load_note(dir, &name) {
+                Ok((content, meta, _)) => {
+                    editor.content = content;
+                    editor.current_tags = meta.tags();
+                    editor.current_meta = meta;
+                }
+                Err(_) => editor.modified = true,
+            }
+        }
+        editor.edit_mode = self.edit_mode;
+        editor.edit_buffer = self.edit_buffer;
+        editor.in_multi_line = self.in_multi_line;
+        editor.current_block = self.current_block;
+        editor.last_listing.clear();
+        editor.invalidate_stats_cache();
+    }
+}
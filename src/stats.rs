@@ -1,7 +1,8 @@
 use chrono::{DateTime, Local};
+use crate::file_ops::FileOperations;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub struct NoteStats {
@@ -21,7 +22,7 @@ impl StatsCalculator {
     pub fn calculate_note_stats(
         content: &str,
         current_file: &Option<PathBuf>,
-        notes_dir: &PathBuf,
+        notes_dir: &Path,
         current_tags: &[String],
     ) -> std::io::Result<NoteStats> {
         let lines = content.lines().count();
@@ -48,36 +49,25 @@ impl StatsCalculator {
         let mut tag_counts = HashMap::new();
         let mut total_notes = 0;
 
-        for tag in current_tags {
-            *tag_counts.entry(tag.to_string()).or_insert(0) += 1;
+        for tag in FileOperations::expand_tags(current_tags) {
+            *tag_counts.entry(tag).or_insert(0) += 1;
         }
 
-        for entry in fs::read_dir(notes_dir)? {
-            let entry = entry?;
-            if entry.path().extension().map_or(false, |ext| ext == "md") {
-                if let Some(current_path) = current_file {
-                    if entry.path() == *current_path {
-                        continue;
-                    }
+        for path in FileOperations::note_paths(notes_dir)? {
+            if let Some(current_path) = current_file {
+                if path == *current_path {
+                    continue;
                 }
+            }
 
-                total_notes += 1;
-                if let Ok(metadata) = entry.metadata() {
-                    total_size += metadata.len();
-                }
+            total_notes += 1;
+            if let Ok(metadata) = fs::metadata(&path) {
+                total_size += metadata.len();
+            }
 
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if content.starts_with("---\n") {
-                        if let Some(end) = content.find("\n---\n") {
-                            let metadata = &content[4..end];
-                            if let Some(tags) = metadata.strip_prefix("tags: ") {
-                                for tag in tags.split(", ") {
-                                    *tag_counts.entry(tag.to_string()).or_insert(0) += 1;
-                                }
-                            }
-                        }
-                    }
-                }
+            let tags = FileOperations::read_meta(&path).map(|meta| meta.tags()).unwrap_or_default();
+            for tag in FileOperations::expand_tags(&tags) {
+                *tag_counts.entry(tag).or_insert(0) += 1;
             }
         }
 
@@ -86,7 +76,7 @@ impl StatsCalculator {
         }
 
         let mut top_tags: Vec<_> = tag_counts.into_iter().collect();
-        top_tags.sort_by(|a, b| b.1.cmp(&a.1));
+        top_tags.sort_by_key(|tag| std::cmp::Reverse(tag.1));
         top_tags.truncate(2);
 
         let total_size_str = if total_size < 1024 {
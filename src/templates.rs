@@ -0,0 +1,210 @@
+use crate::file_ops::FileOperations;
+use crate::frontmatter::{FieldValue, Frontmatter};
+use chrono::Local;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const VARIABLE_PATTERN: &str = r"\$\$|\{\{\s*([A-Za-z_][A-Za-z0-9_-]*)\s*\}\}|\$([A-Za-z_][A-Za-z0-9_]*)";
+
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "basic",
+        "****Notes ({{date}})\n\n\
+         ****Todo\n- \n",
+    ),
+    (
+        "bug-bounty",
+        "---\ntags: bug-bounty\nvariables: target\n---\n\
+         ****Target: $target\n- program: {{program}}\n- started: {{date}}\n\n\
+         ****Scope\n- in scope: \n- out of scope: \n\n\
+         ****Recon\n- \n\n\
+         ****Findings\n- \n\n\
+         ****Requests\n\n\
+         ****Impact\n\n",
+    ),
+    (
+        "meeting",
+        "---\ntags: meeting\n---\n\
+         ****Meeting: {{name}} ({{datetime}})\n\n\
+         ****Attendees\n- \n\n\
+         ****Agenda\n- \n\n\
+         ****Notes\n\n\
+         ****Action items\n- \n",
+    ),
+    (
+        "research",
+        "---\ntags: research\nvariables: topic\n---\n\
+         ****Topic: $topic\n- started: {{date}}\n\n\
+         ****Questions\n- \n\n\
+         ****Sources\n- \n\n\
+         ****Findings\n\n\
+         ****Next steps\n- \n",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemplateSource {
+    Builtin,
+    User,
+}
+
+pub struct Template {
+    pub name: String,
+    pub source: TemplateSource,
+    pub content: String,
+    pub meta: Frontmatter,
+    pub declared: Vec<String>,
+}
+
+pub struct TemplateManager;
+
+impl TemplateManager {
+    pub fn templates_dir(notes_dir: &Path) -> PathBuf {
+        notes_dir.join("templates")
+    }
+
+    pub fn builtin_names() -> Vec<String> {
+        BUILTIN_TEMPLATES.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    pub fn user_names(notes_dir: &Path) -> io::Result<Vec<String>> {
+        let dir = Self::templates_dir(notes_dir);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut names: Vec<String> = FileOperations::note_paths(&dir)?
+            .iter()
+            .filter_map(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn load(notes_dir: &Path, name: &str) -> io::Result<Template> {
+        let name = name.trim_end_matches(".md");
+        let dir = Self::templates_dir(notes_dir);
+
+        let (source, content, mut meta) = if dir.join(format!("{}.md", name)).exists() {
+            let (content, meta, _) = FileOperations::load_note(&dir, name)?;
+            (TemplateSource::User, content, meta)
+        } else {
+            match BUILTIN_TEMPLATES.iter().find(|(builtin, _)| *builtin == name) {
+                Some((_, raw)) => {
+                    let (meta, content) = Frontmatter::parse(raw);
+                    (TemplateSource::Builtin, content, meta)
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("template not found: {}", name),
+                    ))
+                }
+            }
+        };
+
+        let declared = match meta.get("variables") {
+            Some(FieldValue::List(items)) => items.iter().map(|item| item.to_lowercase()).collect(),
+            Some(FieldValue::Scalar(value)) => value
+                .split(',')
+                .map(|item| item.trim().to_lowercase())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            None => Vec::new(),
+        };
+        meta.remove("variables");
+
+        Ok(Template {
+            name: name.to_string(),
+            source,
+            content,
+            meta,
+            declared,
+        })
+    }
+
+    fn variable(caps: &Captures, declared: &[String]) -> Option<String> {
+        match (caps.get(1), caps.get(2)) {
+            (Some(name), _) => Some(name.as_str().to_lowercase()),
+            (None, Some(name)) => Some(name.as_str().to_lowercase()).filter(|name| declared.contains(name)),
+            (None, None) => None,
+        }
+    }
+
+    pub fn variables(template: &Template) -> Vec<String> {
+        let re = Regex::new(VARIABLE_PATTERN).expect("invalid built-in pattern");
+        let mut names: Vec<String> = Vec::new();
+        for caps in re.captures_iter(&template.content) {
+            if let Some(name) = Self::variable(&caps, &template.declared) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    pub fn builtin_values(note_name: Option<&str>) -> HashMap<String, String> {
+        let now = Local::now();
+        let mut values = HashMap::new();
+        values.insert("date".to_string(), now.format("%Y-%m-%d").to_string());
+        values.insert("time".to_string(), now.format("%H:%M").to_string());
+        values.insert("datetime".to_string(), now.format("%Y-%m-%d %H:%M").to_string());
+        values.insert("name".to_string(), note_name.unwrap_or("untitled").to_string());
+        values
+    }
+
+    pub fn render(template: &Template, values: &HashMap<String, String>) -> String {
+        let re = Regex::new(VARIABLE_PATTERN).expect("invalid built-in pattern");
+        re.replace_all(&template.content, |caps: &Captures| {
+            if &caps[0] == "$$" {
+                return "$".to_string();
+            }
+            Self::variable(caps, &template.declared)
+                .and_then(|name| values.get(&name).cloned())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+    }
+
+    pub fn save(notes_dir: &Path, name: &str, content: &str, meta: &Frontmatter) -> io::Result<PathBuf> {
+        let dir = Self::templates_dir(notes_dir);
+        fs::create_dir_all(&dir)?;
+        FileOperations::save_note(&dir, content, meta, Some(name.trim_end_matches(".md")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(content: &str, declared: &[&str]) -> Template {
+        Template {
+            name: "test".to_string(),
+            source: TemplateSource::User,
+            content: content.to_string(),
+            meta: Frontmatter::default(),
+            declared: declared.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn only_declared_bare_variables_are_filled() {
+        let template = template("cd $HOME for $target and {{host}}, pid $$", &["target"]);
+        assert_eq!(TemplateManager::variables(&template), vec!["target", "host"]);
+
+        let values = HashMap::from([
+            ("home".to_string(), "nope".to_string()),
+            ("target".to_string(), "acme.com".to_string()),
+            ("host".to_string(), "a.acme.com".to_string()),
+        ]);
+        assert_eq!(
+            TemplateManager::render(&template, &values),
+            "cd $HOME for acme.com and a.acme.com, pid $"
+        );
+    }
+}